@@ -1,37 +1,1238 @@
 use inquire::{
-    CustomType, Password,
+    Confirm, CustomType, Password, Select, Text,
     ui::RenderConfig,
     validator::{ErrorMessage, Validation},
 };
-use xinyin::generate_words32;
+use rand::rngs::OsRng;
+use xinyin::{
+    Argon2Params, COIN_FLIPS_FOR_256_BITS, CharsetFilter, CustomCharset, DICE_ROLLS_FOR_256_BITS,
+    Error, Homophones, MAX_CHECKSUM_WORDS, MAX_PARITY_WORDS, MAX_SHARES, MIN_RANGE_COUNT,
+    PinyinSyllable, PrimaryCharset, RADIXES, RecoveryCard, Scheme, SchemeVersion, StrengthLevel,
+    StrengthWarning, UnknownCharacter, UnknownIdiom, UserEntropy, address_of, char_info,
+    combine_words, generate_recovery_card, generate_recovery_card_with_rng, heartprint_fingerprint,
+    heartprint_strength, heartprint_variants, idiom_list, idiom_max_start, import_words_repair,
+    import_words32_repair, normalize_heartprint, normalize_words, pinyin_of, pinyin_to_words_with,
+    rekey_words, rekey_words32, split_words, verify_address,
+};
+
+const ACTION_GENERATE: &str = "生成心印助记字";
+const ACTION_IMPORT: &str = "导入心印助记字";
+const ACTION_CHECK: &str = "检查助记字方案版本";
+const ACTION_SPLIT: &str = "拆分密钥为多份助记字";
+const ACTION_COMBINE: &str = "合并多份助记字还原密钥";
+const ACTION_HEARTPRINT: &str = "查看心印的等价写法";
+const ACTION_REKEY: &str = "更换心印或方案参数";
 
 fn main() {
+    let action = Select::new(
+        "选择操作:",
+        vec![
+            ACTION_GENERATE,
+            ACTION_IMPORT,
+            ACTION_SPLIT,
+            ACTION_COMBINE,
+            ACTION_CHECK,
+            ACTION_REKEY,
+            ACTION_HEARTPRINT,
+        ],
+    )
+    .prompt()
+    .expect("Failed to read action");
+
+    match action {
+        ACTION_GENERATE => generate(),
+        ACTION_IMPORT => import(),
+        ACTION_SPLIT => split(),
+        ACTION_COMBINE => combine(),
+        ACTION_CHECK => check(),
+        ACTION_REKEY => rekey(),
+        ACTION_HEARTPRINT => heartprint(),
+        _ => unreachable!(),
+    }
+}
+
+fn generate() {
     let xinyin_words = Password::new("输入铭记于心的一句话:")
         .with_custom_confirmation_message("重复输入，用于确认:")
         .with_custom_confirmation_error_message("两次输入不一致，请重新输入")
         .prompt()
         .expect("Failed to read password");
 
-    let (start, count) = prompt_sub_chartset_range()
+    let charset_scheme = prompt_charset_scheme();
+
+    let checksum = prompt_checksum().prompt().expect("Failed to read checksum");
+
+    let parity = prompt_parity().prompt().expect("Failed to read parity");
+
+    let argon2 = prompt_argon2()
         .prompt()
-        .expect("Failed to read range");
+        .expect("Failed to read argon2 params");
+
+    if !confirm_heartprint_strength(&xinyin_words, &argon2) {
+        return;
+    }
+
+    let label = prompt_label().prompt().expect("Failed to read label");
+
+    let strip_punctuation = prompt_strip_punctuation()
+        .prompt()
+        .expect("Failed to read punctuation option");
+
+    let salted = Confirm::new("是否加随机盐?")
+        .with_default(false)
+        .with_help_message("加盐后必须保存恢复卡才能还原，心印被猜中也无法单凭助记字还原")
+        .prompt()
+        .expect("Failed to read salt option");
 
     let secret_key = prompt_secret_key()
         .prompt()
         .expect("Failed to read secret key");
 
-    let words = generate_words32(&xinyin_words, start, count, secret_key.as_deref())
-        .expect("Failed to generate words");
-    println!("生成的32个字: {:?}", words);
+    // 随机生成密钥时，可以再混入用户提供的熵
+    let entropy = match secret_key {
+        Some(_) => None,
+        None => prompt_user_entropy(),
+    };
+
+    // 成语本身就容易口述，不支持拼音输入
+    let (with_pinyin, spoken) = if charset_scheme.idioms {
+        (false, false)
+    } else {
+        let with_pinyin = Confirm::new("是否同时输出拼音?")
+            .with_default(false)
+            .with_help_message("在没有中文输入法的电脑上还原时，可以按拼音输入助记字")
+            .prompt()
+            .expect("Failed to read pinyin option");
+
+        let spoken = Confirm::new("是否输出口述用的说法?")
+            .with_default(charset_scheme.homophones != Homophones::Allowed)
+            .with_help_message("每个字配一个常用词，如「潮 — 潮流的潮」，便于电话中念给家人")
+            .prompt()
+            .expect("Failed to read spoken option");
+        (with_pinyin, spoken)
+    };
+
+    let mut scheme = Scheme {
+        checksum,
+        parity,
+        argon2,
+        label,
+        strip_punctuation,
+        ..charset_scheme
+    };
+    if salted {
+        scheme = scheme.with_random_salt().expect("Failed to generate salt");
+    }
+    let card = match secret_key {
+        Some(secret_key) => generate_recovery_card(&xinyin_words, &scheme, Some(&secret_key)),
+        None => {
+            generate_recovery_card_with_rng(&xinyin_words, &scheme, &mut OsRng, entropy.as_ref())
+        }
+    }
+    .expect("Failed to generate words");
+    println!("生成的心印助记字: {:?}", card.words);
+    if with_pinyin {
+        println!("拼音: {}", words_with_pinyin(&card.words));
+    }
+    if spoken {
+        println!("口述:");
+        for line in spoken_words(&card.words) {
+            println!("  {}", line);
+        }
+    }
+    println!("恢复卡: {}", card);
+    println!("请抄录或打印恢复卡，还原时只需要恢复卡和心印");
+    print_fingerprint(&xinyin_words, &scheme);
+    println!("请记下心印指纹，导入时会显示同一个指纹，不一致说明两次输入的心印不同");
+}
+
+fn import() {
+    let xinyin_words = Password::new("输入铭记于心的一句话:")
+        .without_confirmation()
+        .prompt()
+        .expect("Failed to read password");
+
+    let card = prompt_recovery_card()
+        .prompt()
+        .expect("Failed to read recovery card");
+
+    // 恢复卡记录了校验字个数，密钥可以是任意长度；手动输入时按 32 字节密钥推断校验字个数
+    let (mut scheme, words32, from_card) = match card {
+        Some(card) => (card.scheme, card.words, true),
+        None => {
+            let (scheme, words32) = prompt_scheme_and_words();
+            (scheme, words32, false)
+        }
+    };
+    if !load_custom_charset(&mut scheme) {
+        return;
+    }
+    let expected_address = Text::new("输入钱包地址:")
+        .with_help_message("用于核对导入的密钥是否正确，留空表示不核对")
+        .prompt()
+        .expect("Failed to read address");
+
+    let mut words32 = words32.trim().to_string();
+    if words32.chars().any(|c| c.is_ascii_alphabetic()) {
+        match pinyin_to_words_with(&words32, &xinyin_words, &scheme, choose_homophone) {
+            Ok(words) => {
+                println!("对应的心印助记字: {}", words);
+                words32 = words;
+            }
+            Err(err) => {
+                println!("拼音转换失败: {}", err);
+                return;
+            }
+        }
+    }
+
+    // 去掉分隔符、繁体字换成规范字，之后提示的位置都按整理后的助记字计算
+    let normalized = normalize_words(&words32);
+    if normalized.is_changed() {
+        for replaced in &normalized.replaced {
+            println!(
+                "第 {} 个字「{}」按「{}」处理",
+                replaced.position + 1,
+                replaced.from,
+                replaced.to
+            );
+        }
+        println!("整理后的心印助记字: {}", normalized.words);
+    }
+    words32 = normalized.words;
+
+    // 手动输入方案参数时，版本由助记字的版本标记字决定
+    let fingerprint_scheme = match SchemeVersion::detect(&words32) {
+        Ok((version, _)) if !from_card => Scheme {
+            version,
+            ..scheme.clone()
+        },
+        _ => scheme.clone(),
+    };
+    print_fingerprint(&xinyin_words, &fingerprint_scheme);
+
+    loop {
+        let imported = if from_card {
+            import_words_repair(&words32, &xinyin_words, &scheme)
+        } else {
+            import_words32_repair(&words32, &xinyin_words, &scheme)
+                .map(|(secret_key, repaired)| (secret_key.to_vec(), repaired))
+        };
+        match imported {
+            Ok((secret, repaired)) => {
+                if !repaired.is_empty() {
+                    let positions = repaired
+                        .iter()
+                        .map(|i| (i + 1).to_string())
+                        .collect::<Vec<String>>();
+                    let unit = if scheme.idioms { "条成语" } else { "个字" };
+                    println!("已纠正第 {} {}", positions.join("、"), unit);
+                }
+                let Ok(secret_key) = <[u8; 32]>::try_from(secret.as_slice()) else {
+                    if !expected_address.trim().is_empty() {
+                        println!("密钥不是 32 字节，无法核对钱包地址");
+                    }
+                    println!("Secret({} 字节): {:?}", secret.len(), secret);
+                    warn_if_weak(&words32);
+                    break;
+                };
+                if !expected_address.trim().is_empty()
+                    && let Err(err) = verify_address(&secret_key, &expected_address)
+                {
+                    match err {
+                        Error::AddressMismatch { actual, .. } => {
+                            println!("地址不符: 导入的密钥对应 {}，心印或助记字有误", actual)
+                        }
+                        err => println!("核对地址失败: {}", err),
+                    }
+                    break;
+                }
+                println!("钱包地址: {}", address_of(&secret_key));
+                println!("SecretKey: {:?}", secret_key);
+                warn_if_weak(&words32);
+            }
+            Err(Error::ChecksumMismatch) => {
+                println!("校验字不匹配，助记字中有抄错的字，请对照备份仔细检查");
+            }
+            Err(err) => {
+                if let Error::UnknownCharacters(unknowns) = &err
+                    && let Some(corrected) = choose_suggestions(&words32, unknowns)
+                {
+                    words32 = corrected;
+                    continue;
+                }
+                if let Error::UnknownIdioms(unknowns) = &err
+                    && let Some(corrected) = choose_idiom_suggestions(&words32, unknowns)
+                {
+                    words32 = corrected;
+                    continue;
+                }
+                println!("导入失败: {}", err);
+            }
+        }
+        break;
+    }
+}
+
+/// 把密钥拆成多份，每份输出为一张恢复卡，任意门限份即可还原
+fn split() {
+    let xinyin_words = Password::new("输入铭记于心的一句话:")
+        .with_custom_confirmation_message("重复输入，用于确认:")
+        .with_custom_confirmation_error_message("两次输入不一致，请重新输入")
+        .prompt()
+        .expect("Failed to read password");
+
+    let charset_scheme = prompt_charset_scheme();
+
+    let checksum = prompt_checksum().prompt().expect("Failed to read checksum");
+
+    let parity = prompt_parity().prompt().expect("Failed to read parity");
+
+    let argon2 = prompt_argon2()
+        .prompt()
+        .expect("Failed to read argon2 params");
+
+    if !confirm_heartprint_strength(&xinyin_words, &argon2) {
+        return;
+    }
+
+    let label = prompt_label().prompt().expect("Failed to read label");
+
+    let strip_punctuation = prompt_strip_punctuation()
+        .prompt()
+        .expect("Failed to read punctuation option");
+
+    let (threshold, shares) = prompt_threshold()
+        .prompt()
+        .expect("Failed to read threshold");
+
+    let Some(secret_key) = prompt_secret_key()
+        .prompt()
+        .expect("Failed to read secret key")
+    else {
+        println!("拆分需要指定要保护的密钥");
+        return;
+    };
+
+    let scheme = Scheme {
+        checksum,
+        parity,
+        argon2,
+        label,
+        strip_punctuation,
+        ..charset_scheme
+    };
+    let words = split_words(&xinyin_words, &scheme, &secret_key, threshold, shares)
+        .expect("Failed to split secret key");
+    for (i, words) in words.into_iter().enumerate() {
+        println!(
+            "第 {} 份恢复卡: {}",
+            i + 1,
+            RecoveryCard::new(scheme.clone(), words)
+        );
+    }
+    println!(
+        "请把恢复卡分别交给不同的人保管，任意 {} 份加上心印即可还原",
+        threshold
+    );
+    print_fingerprint(&xinyin_words, &scheme);
+    println!("请记下心印指纹，合并时会显示同一个指纹，不一致说明两次输入的心印不同");
+}
+
+/// 用新的心印和方案参数重新加密已有的助记字，钱包地址不变
+fn rekey() {
+    let old_xinyin_words = Password::new("输入原来的心印:")
+        .without_confirmation()
+        .prompt()
+        .expect("Failed to read password");
+
+    let card = prompt_recovery_card()
+        .prompt()
+        .expect("Failed to read recovery card");
+    // 恢复卡中的密钥可以是任意长度，手动输入时按 32 字节的钱包密钥处理
+    let from_card = card.is_some();
+    let (mut old_scheme, old_words) = match card {
+        Some(card) => (card.scheme, card.words),
+        None => prompt_scheme_and_words(),
+    };
+    if !load_custom_charset(&mut old_scheme) {
+        return;
+    }
+    let expected_address = Text::new("输入钱包地址:")
+        .with_help_message("用于核对原来的心印和助记字是否正确，留空表示不核对")
+        .prompt()
+        .expect("Failed to read address");
+
+    println!("接下来设置新的心印和方案参数");
+    let new_xinyin_words = Password::new("输入新的心印:")
+        .with_custom_confirmation_message("重复输入，用于确认:")
+        .with_custom_confirmation_error_message("两次输入不一致，请重新输入")
+        .prompt()
+        .expect("Failed to read password");
+
+    let charset_scheme = prompt_charset_scheme();
+
+    let checksum = prompt_checksum().prompt().expect("Failed to read checksum");
+
+    let parity = prompt_parity().prompt().expect("Failed to read parity");
+
+    let argon2 = prompt_argon2()
+        .prompt()
+        .expect("Failed to read argon2 params");
+
+    if !confirm_heartprint_strength(&new_xinyin_words, &argon2) {
+        return;
+    }
+
+    let label = prompt_label().prompt().expect("Failed to read label");
+
+    let strip_punctuation = prompt_strip_punctuation()
+        .prompt()
+        .expect("Failed to read punctuation option");
+
+    let salted = Confirm::new("是否加随机盐?")
+        .with_default(false)
+        .with_help_message("加盐后必须保存恢复卡才能还原，心印被猜中也无法单凭助记字还原")
+        .prompt()
+        .expect("Failed to read salt option");
+
+    let mut new_scheme = Scheme {
+        checksum,
+        parity,
+        argon2,
+        label,
+        strip_punctuation,
+        ..charset_scheme
+    };
+    if salted {
+        new_scheme = new_scheme
+            .with_random_salt()
+            .expect("Failed to generate salt");
+    }
+    let expected_address = Some(expected_address.trim()).filter(|address| !address.is_empty());
+    let rekeyed = if from_card {
+        rekey_words(
+            old_words.trim(),
+            &old_xinyin_words,
+            &old_scheme,
+            &new_xinyin_words,
+            &new_scheme,
+            expected_address,
+        )
+        .map(|(words, secret)| {
+            let address = <[u8; 32]>::try_from(secret.as_slice())
+                .ok()
+                .map(|secret_key| address_of(&secret_key));
+            (words, address)
+        })
+    } else {
+        rekey_words32(
+            old_words.trim(),
+            &old_xinyin_words,
+            &old_scheme,
+            &new_xinyin_words,
+            &new_scheme,
+            expected_address,
+        )
+        .map(|(words, address)| (words, Some(address)))
+    };
+    match rekeyed {
+        Ok((words, address)) => {
+            if let Some(address) = address {
+                println!("钱包地址: {}（未改变）", address);
+            }
+            println!(
+                "新的恢复卡: {}",
+                RecoveryCard::new(new_scheme.clone(), words)
+            );
+            print_fingerprint(&new_xinyin_words, &new_scheme);
+            println!("请抄录新的恢复卡并记下心印指纹，确认可以还原后再销毁旧的备份");
+        }
+        Err(Error::ChecksumMismatch) => {
+            println!("校验字不匹配，原来的助记字中有抄错的字，请对照备份仔细检查");
+        }
+        Err(Error::AddressMismatch { actual, .. }) => {
+            println!(
+                "地址不符: 原来的助记字对应 {}，原来的心印或助记字有误",
+                actual
+            );
+        }
+        Err(Error::WrongLength(_)) if from_card && expected_address.is_some() => {
+            println!("密钥不是 32 字节，无法核对钱包地址");
+        }
+        Err(err) => println!("重新加密失败: {}", err),
+    }
+}
+
+/// 逐份输入恢复卡，合并还原密钥
+fn combine() {
+    let xinyin_words = Password::new("输入铭记于心的一句话:")
+        .without_confirmation()
+        .prompt()
+        .expect("Failed to read password");
+
+    let mut cards: Vec<RecoveryCard> = Vec::new();
+    loop {
+        let message = format!("输入第 {} 份恢复卡:", cards.len() + 1);
+        let Some(card) = CustomType {
+            message: &message,
+            ..prompt_recovery_card()
+        }
+        .with_help_message("全部输入后直接回车")
+        .prompt()
+        .expect("Failed to read recovery card") else {
+            break;
+        };
+        if cards
+            .first()
+            .is_some_and(|first| first.scheme != card.scheme)
+        {
+            println!("这份恢复卡的方案参数与第 1 份不同，不属于同一个密钥");
+            continue;
+        }
+        cards.push(card);
+    }
+
+    let Some(first) = cards.first_mut() else {
+        println!("没有输入恢复卡");
+        return;
+    };
+    if !load_custom_charset(&mut first.scheme) {
+        return;
+    }
+    let scheme = first.scheme.clone();
+    print_fingerprint(&xinyin_words, &scheme);
+    let words = cards
+        .iter()
+        .map(|card| card.words.as_str())
+        .collect::<Vec<&str>>();
+    match combine_words(&words, &xinyin_words, &scheme) {
+        Ok(secret) => match <[u8; 32]>::try_from(secret.as_slice()) {
+            Ok(secret_key) => {
+                println!("钱包地址: {}", address_of(&secret_key));
+                println!("SecretKey: {:?}", secret_key);
+            }
+            Err(_) => println!("Secret({} 字节): {:?}", secret.len(), secret.as_slice()),
+        },
+        Err(Error::NotEnoughShares { threshold, got }) => {
+            println!("份数不够: 需要 {} 份，只输入了 {} 份", threshold, got);
+        }
+        Err(Error::ChecksumMismatch) => {
+            println!("校验字不匹配，某份恢复卡中有抄错的字，请对照备份仔细检查");
+        }
+        Err(err) => println!("合并失败: {}", err),
+    }
+}
+
+/// 没有恢复卡时，逐项输入方案参数和助记字
+fn prompt_scheme_and_words() -> (Scheme, String) {
+    let charset_scheme = prompt_charset_scheme();
+
+    let parity = prompt_parity()
+        .with_starting_input("0")
+        .with_default(0)
+        .prompt()
+        .expect("Failed to read parity");
+
+    let argon2 = prompt_argon2()
+        .prompt()
+        .expect("Failed to read argon2 params");
+
+    let label = prompt_label().prompt().expect("Failed to read label");
+
+    let strip_punctuation = prompt_strip_punctuation()
+        .prompt()
+        .expect("Failed to read punctuation option");
+
+    let words32 = Text::new("输入心印助记字:")
+        .with_help_message(
+            "可以用顿号、空格分隔，繁体字按规范字处理；没有中文输入法时可以输入拼音，如 wen4 dou3 ting2；有纠错字时，无法辨认的字可以用 ? 代替，无法辨认的成语用 ???? 代替",
+        )
+        .prompt()
+        .expect("Failed to read words");
+
+    // 拼音无法输入版本标记字，需要单独选择方案版本
+    let version = if words32.chars().any(|c| c.is_ascii_alphabetic()) {
+        prompt_version()
+    } else {
+        SchemeVersion::V1
+    };
+
+    let scheme = Scheme {
+        version,
+        parity,
+        argon2,
+        label,
+        strip_punctuation,
+        ..charset_scheme
+    };
+    (scheme, words32)
+}
+
+fn check() {
+    let words = Text::new("输入心印助记字:")
+        .prompt()
+        .expect("Failed to read words");
+    let words = normalize_words(&words).words;
+
+    match SchemeVersion::detect(&words) {
+        Ok((version, _)) => {
+            println!("方案版本: v{}", version.number());
+            if !warn_if_weak(&words) {
+                println!("该版本的字表由 Argon2 派生，无需迁移");
+            }
+        }
+        Err(err) => println!("无法识别方案版本: {:?}", err),
+    }
+}
+
+/// 显示心印的规范形式，以及规范化后相同、都能还原同一个钱包的几种写法（v3 及以后的版本）
+fn heartprint() {
+    let xinyin_words = Password::new("输入铭记于心的一句话:")
+        .without_confirmation()
+        .prompt()
+        .expect("Failed to read password");
+    let strip_punctuation = prompt_strip_punctuation()
+        .prompt()
+        .expect("Failed to read punctuation option");
+
+    println!(
+        "规范形式: {:?}",
+        normalize_heartprint(&xinyin_words, strip_punctuation)
+    );
+    println!("以下写法都视为同一句心印:");
+    for (description, variant) in heartprint_variants(&xinyin_words, strip_punctuation) {
+        println!("  {:?} — {}", variant, description);
+    }
+    println!("v1、v2 的心印不做规范化，必须与生成时逐字相同");
+}
+
+/// 助记字使用的方案字表派生较弱时提示用户迁移，返回是否较弱
+fn warn_if_weak(words: &str) -> bool {
+    let weak = SchemeVersion::detect(words).is_ok_and(|(version, _)| version.is_weak());
+    if weak {
+        println!(
+            "警告: 该助记字使用旧版方案，字表未经 Argon2 派生，助记字泄露后心印可被快速暴力猜测；建议用“更换心印或方案参数”换成最新版本（v{}）",
+            SchemeVersion::LATEST.number()
+        );
+    }
+    weak
+}
+
+/// 显示心印指纹，与生成时记下的指纹对照，可以发现心印输错
+fn print_fingerprint(xinyin_words: &str, scheme: &Scheme) {
+    match heartprint_fingerprint(xinyin_words, scheme) {
+        Ok(fingerprint) => println!(
+            "心印指纹: {}（{}色、{}）",
+            fingerprint, fingerprint.color, fingerprint.animal
+        ),
+        Err(err) => println!("计算心印指纹失败: {}", err),
+    }
+}
+
+/// 按 Argon2 参数估计心印的破解成本并提示，弱的心印不能使用，中等的需要用户确认
+fn confirm_heartprint_strength(xinyin_words: &str, argon2: &Argon2Params) -> bool {
+    let strength = heartprint_strength(xinyin_words, argon2);
+    let level = match strength.level {
+        StrengthLevel::Weak => "弱",
+        StrengthLevel::Fair => "中等",
+        StrengthLevel::Strong => "强",
+    };
+    println!(
+        "心印强度: {}（约 {:.0} 位，按当前 Argon2 参数平均破解成本{}）",
+        level,
+        strength.entropy_bits,
+        format_usd(strength.crack_cost_usd)
+    );
+    for warning in &strength.warnings {
+        match warning {
+            StrengthWarning::TooShort(len) => {
+                println!("  心印只有 {} 个字，建议 20 个字符以上", len)
+            }
+            StrengthWarning::KnownPhrase(phrase) => {
+                println!("  「{}」是常见的诗句、成语或常用语", phrase)
+            }
+            StrengthWarning::Date(date) => println!("  「{}」是日期，容易被猜到", date),
+            StrengthWarning::Pattern(pattern) => {
+                println!("  「{}」是连续或重复的字符", pattern)
+            }
+        }
+    }
+    match strength.level {
+        StrengthLevel::Weak => {
+            println!("心印太弱，请换一句更长、更少见、只有自己知道的话");
+            false
+        }
+        StrengthLevel::Fair => Confirm::new("心印强度一般，仍然使用这句心印?")
+            .with_default(false)
+            .with_help_message("建议加长心印，或调高 Argon2 的内存开销和迭代次数")
+            .prompt()
+            .expect("Failed to read confirmation"),
+        StrengthLevel::Strong => true,
+    }
+}
+
+/// 美元金额的中文写法，如 `约 1.4 亿美元`
+fn format_usd(usd: f64) -> String {
+    match usd {
+        usd if usd < 1.0 => "不到 1 美元".to_string(),
+        usd if usd < 1e4 => format!("约 {:.0} 美元", usd),
+        usd if usd < 1e8 => format!("约 {:.1} 万美元", usd / 1e4),
+        usd if usd < 1e12 => format!("约 {:.1} 亿美元", usd / 1e8),
+        usd if usd < 1e16 => format!("约 {:.1} 万亿美元", usd / 1e12),
+        _ => "超过一亿亿美元".to_string(),
+    }
+}
+
+/// 选择编码方式、基础字集及范围和心印字表的选项，返回最新版本的方案
+///
+/// 成语编码时范围从内置成语表中选取，不需要基础字集、选字条件、形近字和同音字选项。
+fn prompt_charset_scheme() -> Scheme {
+    let idioms = Confirm::new("是否用成语编码?")
+        .with_default(false)
+        .with_help_message(
+            "以四字成语代替单字，每条成语表示 12 位，4096 条成语时 32 字节的密钥为 22 条成语，比单字好记；需要保存恢复卡",
+        )
+        .prompt()
+        .expect("Failed to read idiom option");
+    if idioms {
+        let radix = prompt_radix(true);
+        let len = idiom_list().len();
+        let (start, count) = prompt_sub_chartset_range(len, idiom_max_start(), true)
+            .prompt()
+            .expect("Failed to read range");
+        return Scheme {
+            idioms,
+            radix,
+            ..Scheme::new(SchemeVersion::LATEST, start, count)
+        };
+    }
+
+    let primary = prompt_primary_charset();
+    let filter = prompt_charset_filter(&primary);
+    let radix = prompt_radix(false);
+    let (start, count) = prompt_sub_chartset_range(
+        primary.char_count(),
+        primary.max_start(),
+        filter.is_some() || radix > 256,
+    )
+    .prompt()
+    .expect("Failed to read range");
+
+    let confusable_free = prompt_confusable_free()
+        .prompt()
+        .expect("Failed to read confusable option");
+
+    let homophones = prompt_homophones();
+
+    Scheme {
+        primary,
+        filter,
+        confusable_free,
+        homophones,
+        radix,
+        ..Scheme::new(SchemeVersion::LATEST, start, count)
+    }
+}
+
+/// 选择基础字集，自备字集需要输入字集文件的路径
+fn prompt_primary_charset() -> PrimaryCharset {
+    let mut options = PrimaryCharset::BUILTIN
+        .iter()
+        .map(|charset| charset.description().to_string())
+        .collect::<Vec<String>>();
+    options.push("自备字集文件".to_string());
+    let choice = Select::new("选择基础字集:", options)
+        .with_help_message("字集不是默认的通用规范汉字表时，需要保存恢复卡")
+        .raw_prompt()
+        .expect("Failed to read charset");
+    if let Some(charset) = PrimaryCharset::BUILTIN.get(choice.index) {
+        return charset.clone();
+    }
+
+    loop {
+        let text = prompt_charset_file();
+        match CustomCharset::parse(&text) {
+            Ok(custom) => return PrimaryCharset::Custom(custom),
+            Err(err) => println!("字集文件不可用: {}", err),
+        }
+    }
+}
+
+/// 默认字集可以再按字表级别、笔画数和字频选字，返回 `None` 表示使用整段范围
+fn prompt_charset_filter(primary: &PrimaryCharset) -> Option<CharsetFilter> {
+    const NO_FILTER: &str = "不筛选，使用连续的范围";
+    const CUSTOM_FILTER: &str = "自定义条件";
+
+    if *primary != PrimaryCharset::default() {
+        return None;
+    }
+    let mut options = vec![NO_FILTER.to_string()];
+    options.extend(
+        CharsetFilter::PRESETS
+            .iter()
+            .map(|(_, description, _)| description.to_string()),
+    );
+    options.push(CUSTOM_FILTER.to_string());
+    let choice = Select::new("选择选字条件:", options)
+        .with_help_message(
+            "只用一级字、笔画少的字或最常用的字，助记字更容易抄写和输入；需要保存恢复卡",
+        )
+        .raw_prompt()
+        .expect("Failed to read charset filter");
+    if choice.index == 0 {
+        return None;
+    }
+    if let Some((_, _, filter)) = CharsetFilter::PRESETS.get(choice.index - 1) {
+        return Some(filter.clone());
+    }
+
+    let words = primary.chars().expect("Failed to read charset");
+    let filter = CustomType::<CharsetFilter>::new("输入选字条件:")
+        .with_placeholder("level<=1,strokes<=12,rank<=3000")
+        .with_help_message("level 为字表级别（1–3），strokes 为笔画数，rank 为字频序号，用逗号分隔")
+        .with_validator(move |filter: &CharsetFilter| {
+            Ok(match filter.apply(&words) {
+                Ok(_) => Validation::Valid,
+                Err(err) => Validation::Invalid(ErrorMessage::Custom(err.to_string())),
+            })
+        })
+        .with_error_message("选字条件格式不正确，例如 level<=1,strokes<=12")
+        .prompt()
+        .expect("Failed to read charset filter");
+    Some(filter)
+}
+
+fn prompt_charset_file() -> String {
+    loop {
+        let path = Text::new("输入字集文件的路径:")
+            .with_help_message("UTF-8 文本，每个字只出现一次，空白和以 # 开头的行会被忽略")
+            .prompt()
+            .expect("Failed to read charset path");
+        match std::fs::read_to_string(path.trim()) {
+            Ok(text) => return text,
+            Err(err) => println!("读取字集文件失败: {}", err),
+        }
+    }
+}
+
+/// 恢复卡使用自备字集时，载入字集文件并核对 SHA256，返回是否可以继续导入
+fn load_custom_charset(scheme: &mut Scheme) -> bool {
+    let PrimaryCharset::Custom(custom) = &scheme.primary else {
+        return true;
+    };
+    if custom.is_loaded() {
+        return true;
+    }
+    println!("恢复卡使用了自备字集: {}", scheme.primary);
+    match custom.load(&prompt_charset_file()) {
+        Ok(custom) => {
+            scheme.primary = PrimaryCharset::Custom(custom);
+            true
+        }
+        Err(err) => {
+            println!("字集文件与恢复卡不符: {}", err);
+            false
+        }
+    }
+}
+
+fn prompt_version() -> SchemeVersion {
+    let options = SchemeVersion::ALL
+        .iter()
+        .rev()
+        .map(|version| match version.marker() {
+            Some(marker) => format!("v{}（助记字以 {} 开头）", version.number(), marker),
+            None => format!("v{}（助记字没有标记）", version.number()),
+        })
+        .collect::<Vec<String>>();
+    let choice = Select::new("选择助记字的方案版本:", options)
+        .raw_prompt()
+        .expect("Failed to read scheme version");
+    SchemeVersion::ALL[SchemeVersion::ALL.len() - 1 - choice.index]
+}
+
+/// 拼音对应心印字表中的多个字时，让用户选出抄录的字
+fn choose_homophone(syllable: &PinyinSyllable) -> Option<char> {
+    let message = format!(
+        "第 {} 个字「{}」对应多个字，您抄录的是:",
+        syllable.position + 1,
+        syllable.syllable
+    );
+    Select::new(&message, syllable.candidates.clone())
+        .prompt()
+        .ok()
+}
+
+/// 每个字后面附上拼音，如 `问wen4 抖dou3`，版本标记字原样保留
+fn words_with_pinyin(words: &str) -> String {
+    words
+        .chars()
+        .map(|ch| match pinyin_of(ch).first() {
+            Some(pinyin) => format!("{}{}", ch, pinyin),
+            None => ch.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// 每个字一行，附上口述时区分同音字的说法，如 `潮 — 潮流的潮`
+fn spoken_words(words: &str) -> Vec<String> {
+    words
+        .chars()
+        .map(|ch| {
+            if let Some(version) = SchemeVersion::from_marker(ch) {
+                return format!("{} — 版本标记（v{}）", ch, version.number());
+            }
+            match char_info(ch) {
+                Some(info) => format!("{} — {}", ch, info.spoken()),
+                None => ch.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// 对不在心印字表中的字，让用户从形近字中选出本来要输入的字
+///
+/// 返回替换后的助记字，用户没有做任何替换时返回 `None`
+fn choose_suggestions(words32: &str, unknowns: &[UnknownCharacter]) -> Option<String> {
+    const NONE_OF_THEM: &str = "都不是";
+
+    let (_, without_marker) = SchemeVersion::detect(words32).ok()?;
+    let marker = &words32[..words32.len() - without_marker.len()];
+    let mut chars = without_marker.chars().collect::<Vec<char>>();
+
+    let mut changed = false;
+    for unknown in unknowns.iter().filter(|u| !u.suggestions.is_empty()) {
+        let mut options = unknown
+            .suggestions
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>();
+        options.push(NONE_OF_THEM.to_string());
+
+        let message = format!(
+            "第 {} 个字「{}」不在心印字表中，您要输入的是:",
+            unknown.position + 1,
+            unknown.ch
+        );
+        let choice = Select::new(&message, options)
+            .prompt()
+            .expect("Failed to read suggestion");
+        if let Some(ch) = choice.chars().next().filter(|_| choice != NONE_OF_THEM) {
+            chars[unknown.position] = ch;
+            changed = true;
+        }
+    }
+
+    changed.then(|| format!("{}{}", marker, chars.into_iter().collect::<String>()))
+}
+
+/// 对不在心印成语表中的成语，让用户从只差一个字的成语中选出本来要输入的成语
+///
+/// `words32` 为整理后的助记字，成语之间没有分隔符；用户没有做任何替换时返回 `None`
+fn choose_idiom_suggestions(words32: &str, unknowns: &[UnknownIdiom]) -> Option<String> {
+    const NONE_OF_THEM: &str = "都不是";
+
+    let (_, without_marker) = SchemeVersion::detect(words32).ok()?;
+    let marker = &words32[..words32.len() - without_marker.len()];
+    let chars = without_marker.chars().collect::<Vec<char>>();
+    let mut idioms = chars
+        .chunks(4)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<String>>();
+
+    let mut changed = false;
+    for unknown in unknowns.iter().filter(|u| !u.suggestions.is_empty()) {
+        let mut options = unknown.suggestions.clone();
+        options.push(NONE_OF_THEM.to_string());
+
+        let message = format!(
+            "第 {} 条成语「{}」不在心印成语表中，您要输入的是:",
+            unknown.position + 1,
+            unknown.idiom
+        );
+        let choice = Select::new(&message, options)
+            .prompt()
+            .expect("Failed to read suggestion");
+        if choice != NONE_OF_THEM {
+            idioms[unknown.position] = choice;
+            changed = true;
+        }
+    }
+
+    changed.then(|| format!("{}{}", marker, idioms.join(" ")))
+}
+
+/// 选择心印字表的字数，字数越多助记字越短；成语编码时为心印成语表的条数，默认 4096 条
+fn prompt_radix(idioms: bool) -> usize {
+    let (unit, default) = if idioms {
+        ("条成语", 4096)
+    } else {
+        ("个字", 256)
+    };
+    let options = RADIXES
+        .iter()
+        .map(|&radix| {
+            let len = (32 * 8usize).div_ceil(radix.trailing_zeros() as usize);
+            if radix == default {
+                format!(
+                    "{} {}（默认，32 字节的密钥为 {} {}）",
+                    radix, unit, len, unit
+                )
+            } else {
+                format!("{} {}（32 字节的密钥为 {} {}）", radix, unit, len, unit)
+            }
+        })
+        .collect::<Vec<String>>();
+    let default = RADIXES
+        .iter()
+        .position(|&radix| radix == default)
+        .unwrap_or(0);
+    let choice = Select::new("选择心印字表的字数:", options)
+        .with_starting_cursor(default)
+        .with_help_message("字数越多助记字越短，需要的基础字集范围也越大；非 256 时需要保存恢复卡")
+        .raw_prompt()
+        .expect("Failed to read radix");
+    RADIXES[choice.index]
+}
+
+/// 选择是否去掉同音字
+fn prompt_homophones() -> Homophones {
+    let options = vec![
+        "保留同音字",
+        "去掉同音字（拼音和声调都相同）",
+        "去掉同音字（不论声调，需要较大的选取范围）",
+    ];
+    let choice = Select::new("是否去掉同音字?", options)
+        .with_help_message("去掉后口述助记字时，听到的读音只对应一个字；需要保存恢复卡")
+        .raw_prompt()
+        .expect("Failed to read homophone option");
+    [
+        Homophones::Allowed,
+        Homophones::DistinctTones,
+        Homophones::DistinctSyllables,
+    ][choice.index]
+}
+
+fn prompt_confusable_free() -> Confirm<'static> {
+    Confirm::new("是否去掉形近字?")
+        .with_default(false)
+        .with_help_message(
+            "己已巳、未末这样的形近字每组只保留一个，手抄的助记字不易认错；需要保存恢复卡",
+        )
+}
+
+fn prompt_checksum() -> CustomType<'static, usize> {
+    CustomType::new("输入校验字个数:")
+        .with_starting_input("1")
+        .with_default(1)
+        .with_help_message("校验字附加在密钥对应的字之后，用于发现抄写错误，0 表示不加")
+        .with_validator(|checksum: &usize| {
+            if *checksum > MAX_CHECKSUM_WORDS {
+                Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                    "校验字最多{}个",
+                    MAX_CHECKSUM_WORDS
+                ))))
+            } else {
+                Ok(Validation::Valid)
+            }
+        })
+        .with_error_message("校验字个数格式不正确，输入一个不大于 4 的数字")
+}
+
+fn prompt_parity() -> CustomType<'static, usize> {
+    CustomType::new("输入纠错字个数:")
+        .with_starting_input("4")
+        .with_default(4)
+        .with_help_message("n 个纠错字最多能纠正 n/2 个抄错的字或 n 个无法辨认的字，0 表示不加")
+        .with_validator(|parity: &usize| {
+            if *parity > MAX_PARITY_WORDS {
+                Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                    "纠错字最多{}个",
+                    MAX_PARITY_WORDS
+                ))))
+            } else {
+                Ok(Validation::Valid)
+            }
+        })
+        .with_error_message("纠错字个数格式不正确，输入一个不大于 32 的数字")
+}
+
+fn prompt_argon2() -> CustomType<'static, Argon2Params> {
+    CustomType::new("输入 Argon2 参数:")
+        .with_starting_input("argon2id,m=19456,t=2,p=1")
+        .with_default(Argon2Params::default())
+        .with_help_message(
+            "格式为 算法,m=内存KiB,t=迭代次数,p=并行度；非默认参数需要和助记字一起保存",
+        )
+        .with_error_message("Argon2 参数格式不正确，例如 argon2id,m=19456,t=2,p=1")
+}
+
+fn prompt_recovery_card() -> CustomType<'static, Option<RecoveryCard>> {
+    CustomType {
+        message: "输入恢复卡:",
+        starting_input: None,
+        default: None,
+        placeholder: Some("XINYIN;v=2;range=6,666;words=..."),
+        help_message: Some("没有恢复卡时直接回车，逐项输入参数"),
+        formatter: &|card: Option<RecoveryCard>| match card {
+            Some(card) => card.to_string(),
+            None => "无".to_string(),
+        },
+        default_value_formatter: &|_| "无".to_string(),
+        parser: &|input: &str| {
+            if input.trim().is_empty() {
+                return Ok(None);
+            }
+            input.parse::<RecoveryCard>().map(Some).map_err(|_| ())
+        },
+        validators: vec![],
+        error_message: "恢复卡格式不正确，应以 XINYIN; 开头".to_string(),
+        render_config: RenderConfig::default(),
+    }
+}
+
+fn prompt_threshold() -> CustomType<'static, (usize, usize)> {
+    CustomType {
+        message: "输入门限和份数:",
+        starting_input: Some("2,3"),
+        default: Some((2, 3)),
+        placeholder: None,
+        help_message: Some("例如 2,3 表示拆成 3 份，任意 2 份即可还原"),
+        formatter: &|(threshold, shares)| format!("{},{}", threshold, shares),
+        default_value_formatter: &|(threshold, shares)| format!("{},{}", threshold, shares),
+        parser: &|input: &str| {
+            let (threshold, shares) = input.split_once(',').ok_or(())?;
+            let threshold = threshold.trim().parse::<usize>().map_err(|_| ())?;
+            let shares = shares.trim().parse::<usize>().map_err(|_| ())?;
+            Ok((threshold, shares))
+        },
+        validators: vec![Box::new(|&(threshold, shares): &(usize, usize)| {
+            if threshold < 2 || threshold > shares {
+                Ok(Validation::Invalid(ErrorMessage::Custom(
+                    "门限至少为2，且不能大于份数".to_string(),
+                )))
+            } else if shares > MAX_SHARES {
+                Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                    "最多拆成{}份",
+                    MAX_SHARES
+                ))))
+            } else {
+                Ok(Validation::Valid)
+            }
+        })],
+        error_message: "格式不正确，使用英语输入法输入 门限,份数".to_string(),
+        render_config: RenderConfig::default(),
+    }
+}
+
+fn prompt_strip_punctuation() -> Confirm<'static> {
+    Confirm::new("心印是否忽略标点符号?")
+        .with_default(false)
+        .with_help_message(
+            "忽略后「星辰大海，心自无疆。」与「星辰大海心自无疆」视为同一句心印；需要 v3 及以上版本，需要保存恢复卡",
+        )
+}
+
+fn prompt_label() -> Text<'static> {
+    Text::new("输入钱包标签:").with_help_message(
+        "同一句心印保护多个钱包时，用不同的标签（如 0、1）区分，留空表示不加标签",
+    )
+}
+
+/// 选择是否混入骰子、硬币或随手输入的文本，返回 `None` 表示只用系统随机数
+fn prompt_user_entropy() -> Option<UserEntropy> {
+    const SOURCE_OS: &str = "只用系统随机数";
+    const SOURCE_DICE: &str = "再混入掷骰子的结果";
+    const SOURCE_COINS: &str = "再混入抛硬币的结果";
+    const SOURCE_TEXT: &str = "再混入随手输入的文本";
+
+    let source = Select::new(
+        "随机密钥的来源:",
+        vec![SOURCE_OS, SOURCE_DICE, SOURCE_COINS, SOURCE_TEXT],
+    )
+    .with_help_message(
+        "不信任本机的系统随机数（如老旧设备、虚拟机）时，可以混入自己掷骰子或抛硬币的结果",
+    )
+    .prompt()
+    .expect("Failed to read entropy source");
+
+    match source {
+        SOURCE_DICE => Some(prompt_counted_entropy(
+            "输入掷骰子的点数:",
+            "每次 1–6，可以用空格分隔；99 次约为 256 位",
+            DICE_ROLLS_FOR_256_BITS,
+            UserEntropy::parse_dice,
+        )),
+        SOURCE_COINS => Some(prompt_counted_entropy(
+            "输入抛硬币的结果:",
+            "正面为 H 或 1，反面为 T 或 0，可以用空格分隔",
+            COIN_FLIPS_FOR_256_BITS,
+            UserEntropy::parse_coins,
+        )),
+        SOURCE_TEXT => {
+            let text = Text::new("随手输入一些文本:")
+                .with_help_message("越长越好，不必记住；文本的熵无法估计，与系统随机数混合后使用")
+                .with_validator(|input: &str| {
+                    Ok(if input.trim().is_empty() {
+                        Validation::Invalid(ErrorMessage::Custom("文本不能为空".to_string()))
+                    } else {
+                        Validation::Valid
+                    })
+                })
+                .prompt()
+                .expect("Failed to read entropy text");
+            Some(UserEntropy::Text(text))
+        }
+        _ => None,
+    }
+}
+
+/// 输入骰子或硬币的结果，次数至少为 `min`，以便单独提供约 256 位熵
+fn prompt_counted_entropy(
+    message: &str,
+    help: &str,
+    min: usize,
+    parse: fn(&str) -> xinyin::Result<UserEntropy>,
+) -> UserEntropy {
+    let input = Text::new(message)
+        .with_help_message(help)
+        .with_validator(move |input: &str| {
+            let count = match parse(input) {
+                Ok(UserEntropy::Dice(rolls)) => rolls.len(),
+                Ok(UserEntropy::Coins(flips)) => flips.len(),
+                Ok(UserEntropy::Text(_)) => min,
+                Err(err) => {
+                    return Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                        "格式不正确: {}",
+                        err
+                    ))));
+                }
+            };
+            Ok(if count < min {
+                Validation::Invalid(ErrorMessage::Custom(format!(
+                    "只有 {} 次，至少需要 {} 次",
+                    count, min
+                )))
+            } else {
+                Validation::Valid
+            })
+        })
+        .prompt()
+        .expect("Failed to read entropy");
+    parse(&input).expect("Failed to parse entropy")
 }
 
 fn prompt_secret_key() -> CustomType<'static, Option<Vec<u8>>> {
-    return CustomType {
+    CustomType {
         message: "输入指定SecretKey:",
         starting_input: None,
         default: None,
         placeholder: None,
-        help_message: None,
+        help_message: Some(
+            "可以是任意长度的密钥，如 64 字节的密钥对、16 字节的 BIP39 熵；留空随机生成 32 字节",
+        ),
         formatter: &|key: Option<Vec<u8>>| format!("SecretKey: {:?}", key),
         default_value_formatter: &|key: Option<Vec<u8>>| format!("SecretKey: {:?}", key),
         parser: &|input: &str| {
@@ -54,9 +1255,9 @@ fn prompt_secret_key() -> CustomType<'static, Option<Vec<u8>>> {
         },
         validators: vec![Box::new(|key: &Option<Vec<u8>>| match key {
             Some(vec) => {
-                if vec.len() != 32 {
+                if vec.is_empty() {
                     Ok(Validation::Invalid(ErrorMessage::Custom(
-                        "SecretKey必须是32个字节".to_string(),
+                        "SecretKey不能为空".to_string(),
                     )))
                 } else {
                     Ok(Validation::Valid)
@@ -64,18 +1265,23 @@ fn prompt_secret_key() -> CustomType<'static, Option<Vec<u8>>> {
             }
             None => Ok(Validation::Valid),
         })],
-        error_message: "SecretKey格式不正确，使用英语输入法输入 0,1,2,...,31".to_string(),
+        error_message: "SecretKey格式不正确，使用英语输入法输入 0,1,2,...".to_string(),
         render_config: RenderConfig::default(),
-    };
+    }
 }
 
-fn prompt_sub_chartset_range() -> CustomType<'static, (usize, usize)> {
-    return CustomType {
-        message: "输入选取的规范字范围:",
-        starting_input: Some("6,666"),
-        default: Some((6, 666)),
+/// 有选字条件、心印字表较大或成语编码时默认选取整个字表，`len` 为基础字集的字数或成语表的条数
+fn prompt_sub_chartset_range(
+    len: usize,
+    max_start: usize,
+    whole: bool,
+) -> CustomType<'static, (usize, usize)> {
+    CustomType {
+        message: "输入选取的基础字集范围:",
+        starting_input: (!whole).then_some("6,666"),
+        default: Some(if whole { (1, len - 1) } else { (6, 666) }),
         placeholder: None,
-        help_message: None,
+        help_message: whole.then_some("直接回车选取整个字表"),
         formatter: &|(start, count)| format!("{},{}", start, count),
         default_value_formatter: &|(start, count)| format!("{},{}", start, count),
         parser: &|input: &str| {
@@ -87,26 +1293,29 @@ fn prompt_sub_chartset_range() -> CustomType<'static, (usize, usize)> {
             let count = parts[1].trim().parse::<usize>().map_err(|_| ())?;
             Ok((start, count))
         },
-        validators: vec![Box::new(|(start, count): &(usize, usize)| {
+        validators: vec![Box::new(move |(start, count): &(usize, usize)| {
             let start = *start;
             let count = *count;
-            if count <= 500 {
-                Ok(Validation::Invalid(ErrorMessage::Custom(
-                    "范字范围必须是非负整数且计数大于500".to_string(),
-                )))
-            } else if start < 1 || start > 7600 {
-                Ok(Validation::Invalid(ErrorMessage::Custom(
-                    "范字范围的起始位置必须在1到7600之间".to_string(),
-                )))
-            } else if count + start > 8105 {
-                Ok(Validation::Invalid(ErrorMessage::Custom(
-                    "范字范围超过了8105".to_string(),
-                )))
+            if count <= MIN_RANGE_COUNT {
+                Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                    "范字范围必须是非负整数且计数大于{}",
+                    MIN_RANGE_COUNT
+                ))))
+            } else if !(1..=max_start).contains(&start) {
+                Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                    "范字范围的起始位置必须在1到{}之间",
+                    max_start
+                ))))
+            } else if count + start > len {
+                Ok(Validation::Invalid(ErrorMessage::Custom(format!(
+                    "范字范围超过了{}",
+                    len
+                ))))
             } else {
                 Ok(Validation::Valid)
             }
         })],
         error_message: "范字范围格式不正确，使用英语输入法输入 start,count".to_string(),
         render_config: RenderConfig::default(),
-    };
+    }
 }
@@ -1,7 +1,15 @@
 use xinyin::sks::SksStore;
 
+use js_sys::{Array, Reflect};
 use wasm_bindgen::{JsValue, prelude::wasm_bindgen};
-use xinyin::{EncryptedSk, generate_words32, import_words32};
+use xinyin::{
+    Argon2Params, CharsetFilter, CustomCharset, EncryptedSk, Error, Homophones, PinyinSyllable,
+    PrimaryCharset, RecoveryCard, Scheme, SchemeVersion, StrengthWarning, UnknownCharacter,
+    UnknownIdiom, address_of, char_info, generate_recovery_card, generate_words,
+    generate_words32_with, heartprint_fingerprint, heartprint_strength, heartprint_variants,
+    import_recovery_card, import_words, import_words32_verified_with, import_words32_with,
+    normalize_heartprint, normalize_words, pinyin_of, pinyin_to_words, rekey_words,
+};
 
 #[wasm_bindgen(raw_module = "./xinyin-opfs.ts")]
 extern "C" {
@@ -14,21 +22,18 @@ extern "C" {
 #[wasm_bindgen]
 pub fn sign_message(address: &str, message: &[u8], psw: &str) -> Result<Vec<u8>, JsValue> {
     let encrypted_sk = EncryptedSk::get_by_address(address, psw, &WebSksStore).map_err(|err| {
-        JsValue::from_str(&format!(
-            "get encrypted sk by address({}) failed: {:?}",
-            address, err
-        ))
+        error_to_js(
+            &format!("get encrypted sk by address({}) failed", address),
+            &err,
+        )
     })?;
 
-    match encrypted_sk.sign_message(message, psw) {
-        Ok(signature) => Ok(signature),
-        Err(err) => Err(JsValue::from_str(&format!(
-            "sign message failed: {:?}",
-            err
-        ))),
-    }
+    encrypted_sk
+        .sign_message(message, psw)
+        .map_err(|err| error_to_js("sign message failed", &err))
 }
 
+/// 导入心印密钥并加密保存，`checksum` 为助记字末尾的校验字个数，省略时为 0
 #[wasm_bindgen]
 pub fn import_xinyin_words32(
     words32: &str,
@@ -36,19 +41,48 @@ pub fn import_xinyin_words32(
     start: usize,
     count: usize,
     psw: &str,
+    label: Option<String>,
+    checksum: Option<usize>,
 ) -> Result<String, JsValue> {
-    let sk = match import_words32(words32, txt_in_heart, start, count) {
-        Ok(sk) => sk,
-        Err(err) => {
-            return Err(JsValue::from_str(&format!(
-                "import words32 failed: {:?}",
-                err
-            )));
-        }
+    let scheme = Scheme {
+        label: label.unwrap_or_default(),
+        checksum: checksum.unwrap_or_default(),
+        ..Scheme::v1(start, count)
     };
+    let sk = import_words32_with(words32, txt_in_heart, &scheme)
+        .map_err(|err| error_to_js("import words32 failed", &err))?;
 
     let encrypted_sk = EncryptedSk::encrypt_sk(&sk, psw, &WebSksStore)
-        .map_err(|err| JsValue::from_str(&format!("encrypt sk failed: {:?}", err)))?;
+        .map_err(|err| error_to_js("encrypt sk failed", &err))?;
+
+    Ok(encrypted_sk.address.clone())
+}
+
+/// 导入心印密钥并核对钱包地址，地址不符时不保存密钥
+///
+/// 地址不符时返回的 JS Error 的 `code` 为 `address_mismatch`，`actual` 属性为导入的密钥实际对应的地址
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen]
+pub fn import_xinyin_words32_verified(
+    words32: &str,
+    txt_in_heart: &str,
+    start: usize,
+    count: usize,
+    expected_address: &str,
+    psw: &str,
+    label: Option<String>,
+    checksum: Option<usize>,
+) -> Result<String, JsValue> {
+    let scheme = Scheme {
+        label: label.unwrap_or_default(),
+        checksum: checksum.unwrap_or_default(),
+        ..Scheme::v1(start, count)
+    };
+    let sk = import_words32_verified_with(words32, txt_in_heart, &scheme, expected_address)
+        .map_err(|err| error_to_js("import words32 failed", &err))?;
+
+    let encrypted_sk = EncryptedSk::encrypt_sk(&sk, psw, &WebSksStore)
+        .map_err(|err| error_to_js("encrypt sk failed", &err))?;
 
     Ok(encrypted_sk.address.clone())
 }
@@ -58,20 +92,632 @@ pub fn generate_xinyin_words32(
     txt_in_heart: &str,
     start: usize,
     count: usize,
+    label: Option<String>,
+) -> Result<String, JsValue> {
+    let scheme = Scheme {
+        label: label.unwrap_or_default(),
+        ..Scheme::new(SchemeVersion::LATEST, start, count)
+    };
+    generate_words32_with(txt_in_heart, &scheme, None)
+        .map_err(|err| error_to_js("generate words32 failed", &err))
+}
+
+/// 转换为 JS Error，`code` 属性为 [`Error::code`] 给出的稳定错误码
+///
+/// 部分错误带有额外属性：
+/// * `unknown_characters`：`unknown` 为 `[{ position, ch, suggestions }]`，供页面提示“您是不是要输入”
+/// * `unknown_idioms`：`unknown` 为 `[{ position, idiom, suggestions }]`，`position` 为成语的序号
+/// * `address_mismatch`：`expected`、`actual` 为预期和实际的地址
+/// * `invalid_range`：`start`、`count` 为输入的基础字集范围，`len` 为基础字集的字数
+/// * `charset_mismatch`：`expected`、`actual` 为恢复卡记录的和字集文件实际的 SHA256
+/// * `unknown_pinyin`、`ambiguous_pinyin`：`syllables` 为 `[{ position, syllable, candidates }]`，
+///   页面可让用户从 `candidates` 中选出抄录的字
+fn error_to_js(context: &str, err: &Error) -> JsValue {
+    let error = js_sys::Error::new(&format!("{}: {}", context, err));
+    let _ = Reflect::set(&error, &"code".into(), &err.code().into());
+
+    match err {
+        Error::UnknownCharacters(unknowns) => {
+            let _ = Reflect::set(
+                &error,
+                &"unknown".into(),
+                &unknown_characters_to_js(unknowns),
+            );
+        }
+        Error::UnknownIdioms(unknowns) => {
+            let _ = Reflect::set(&error, &"unknown".into(), &unknown_idioms_to_js(unknowns));
+        }
+        Error::AddressMismatch { expected, actual } => {
+            let _ = Reflect::set(&error, &"expected".into(), &expected.as_str().into());
+            let _ = Reflect::set(&error, &"actual".into(), &actual.as_str().into());
+        }
+        Error::InvalidRange { start, count, len } => {
+            let _ = Reflect::set(&error, &"start".into(), &(*start as u32).into());
+            let _ = Reflect::set(&error, &"count".into(), &(*count as u32).into());
+            let _ = Reflect::set(&error, &"len".into(), &(*len as u32).into());
+        }
+        Error::CharsetMismatch { expected, actual } => {
+            let _ = Reflect::set(&error, &"expected".into(), &expected.as_str().into());
+            let _ = Reflect::set(&error, &"actual".into(), &actual.as_str().into());
+        }
+        Error::UnknownPinyin(syllables) | Error::AmbiguousPinyin(syllables) => {
+            let _ = Reflect::set(
+                &error,
+                &"syllables".into(),
+                &pinyin_syllables_to_js(syllables),
+            );
+        }
+        _ => {}
+    }
+
+    error.into()
+}
+
+fn unknown_characters_to_js(unknowns: &[UnknownCharacter]) -> Array {
+    let items = Array::new();
+    for unknown in unknowns {
+        let item = js_sys::Object::new();
+        let suggestions = unknown
+            .suggestions
+            .iter()
+            .map(|c| JsValue::from_str(&c.to_string()))
+            .collect::<Array>();
+        let _ = Reflect::set(&item, &"position".into(), &(unknown.position as u32).into());
+        let _ = Reflect::set(&item, &"ch".into(), &unknown.ch.to_string().into());
+        let _ = Reflect::set(&item, &"suggestions".into(), &suggestions);
+        items.push(&item);
+    }
+    items
+}
+
+fn unknown_idioms_to_js(unknowns: &[UnknownIdiom]) -> Array {
+    let items = Array::new();
+    for unknown in unknowns {
+        let item = js_sys::Object::new();
+        let suggestions = unknown
+            .suggestions
+            .iter()
+            .map(|idiom| JsValue::from_str(idiom))
+            .collect::<Array>();
+        let _ = Reflect::set(&item, &"position".into(), &(unknown.position as u32).into());
+        let _ = Reflect::set(&item, &"idiom".into(), &unknown.idiom.as_str().into());
+        let _ = Reflect::set(&item, &"suggestions".into(), &suggestions);
+        items.push(&item);
+    }
+    items
+}
+
+fn pinyin_syllables_to_js(syllables: &[PinyinSyllable]) -> Array {
+    let items = Array::new();
+    for syllable in syllables {
+        let item = js_sys::Object::new();
+        let candidates = syllable
+            .candidates
+            .iter()
+            .map(|c| JsValue::from_str(&c.to_string()))
+            .collect::<Array>();
+        let _ = Reflect::set(
+            &item,
+            &"position".into(),
+            &(syllable.position as u32).into(),
+        );
+        let _ = Reflect::set(
+            &item,
+            &"syllable".into(),
+            &syllable.syllable.as_str().into(),
+        );
+        let _ = Reflect::set(&item, &"candidates".into(), &candidates);
+        items.push(&item);
+    }
+    items
+}
+
+/// 把拼音（如 `wen4 dou3`，也可以夹杂汉字）转换为心印助记字
+///
+/// 拼音开头没有版本标记字时按 `version` 指定的方案版本处理。同音字用校验字也无法确定时，
+/// 返回的 JS Error 的 `code` 为 `ambiguous_pinyin`，页面选出后可以把汉字填回拼音中再次转换。
+#[wasm_bindgen]
+pub fn xinyin_pinyin_to_words(
+    pinyin: &str,
+    txt_in_heart: &str,
+    start: usize,
+    count: usize,
+    version: u8,
+    checksum: usize,
+    label: Option<String>,
 ) -> Result<String, JsValue> {
-    match generate_words32(txt_in_heart, start, count, None) {
-        Ok(words32) => Ok(words32),
-        Err(err) => Err(JsValue::from_str(&format!(
-            "generate words32 failed: {:?}",
-            err
-        ))),
+    let version = SchemeVersion::from_number(version).ok_or_else(|| {
+        error_to_js(
+            "pinyin to words failed",
+            &Error::InvalidParams(format!("unknown scheme version {}", version)),
+        )
+    })?;
+    let scheme = Scheme {
+        checksum,
+        label: label.unwrap_or_default(),
+        ..Scheme::new(version, start, count)
+    };
+    pinyin_to_words(pinyin, txt_in_heart, &scheme)
+        .map_err(|err| error_to_js("pinyin to words failed", &err))
+}
+
+/// 每个助记字的拼音（带声调数字），以空格分隔，版本标记字原样保留
+#[wasm_bindgen]
+pub fn xinyin_words_pinyin(words: &str) -> String {
+    words
+        .chars()
+        .map(|ch| match pinyin_of(ch).first() {
+            Some(pinyin) => pinyin.to_string(),
+            None => ch.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// 每个助记字口述时的说法（如 `潮流的潮`），没有收录的字原样保留，版本标记字为 `版本标记`
+#[wasm_bindgen]
+pub fn xinyin_words_spoken(words: &str) -> Array {
+    words
+        .chars()
+        .map(|ch| {
+            let spoken = if SchemeVersion::from_marker(ch).is_some() {
+                "版本标记".to_string()
+            } else {
+                char_info(ch).map_or_else(|| ch.to_string(), |info| info.spoken())
+            };
+            JsValue::from_str(&spoken)
+        })
+        .collect()
+}
+
+/// 生成带校验字的心印助记字，`checksum` 为附加在末尾的校验字个数
+#[wasm_bindgen]
+pub fn generate_xinyin_words32_with_checksum(
+    txt_in_heart: &str,
+    start: usize,
+    count: usize,
+    checksum: usize,
+    label: Option<String>,
+) -> Result<String, JsValue> {
+    let scheme = Scheme {
+        checksum,
+        label: label.unwrap_or_default(),
+        ..Scheme::new(SchemeVersion::LATEST, start, count)
+    };
+    generate_words32_with(txt_in_heart, &scheme, None)
+        .map_err(|err| error_to_js("generate words32 failed", &err))
+}
+
+/// 生成恢复卡，返回恢复卡的文本形式，方案参数 `options` 见 [`SchemeOptions`]
+#[wasm_bindgen]
+pub fn generate_xinyin_recovery_card(
+    txt_in_heart: &str,
+    options: &JsValue,
+) -> Result<String, JsValue> {
+    let scheme = SchemeOptions::from_js(options)?.into_scheme()?;
+    generate_recovery_card(txt_in_heart, &scheme, None)
+        .map(|card| card.to_string())
+        .map_err(|err| error_to_js("generate recovery card failed", &err))
+}
+
+/// 生成恢复卡和更换心印时的方案参数，由 JS 对象读取，组成最新版本的方案
+///
+/// 除 `start`、`count` 外的字段都可以省略：
+/// * `start`、`count`：基础字集范围；`checksum`、`parity`：校验字和纠错字个数，默认为 0；
+/// * `salted`：为 `true` 时加随机盐；`label`：钱包标签；
+/// * `charset`：基础字集的名称（如 `big5`、`hangul`），省略时为通用规范汉字表；
+///   使用自备字集时为 `custom`，`charsetFile` 为字集文件的内容；
+/// * `filter`：选字条件（如 `level1-simple`、`level<=1,strokes<=12`），只能用于通用规范汉字表；
+/// * `confusableFree`：为 `true` 时去掉形近字；`homophoneFree`：为 `true` 时去掉同音字，
+///   `ignoreTones` 为 `true` 时不论声调；
+/// * `radix`：心印字表的字数（64–4096 之间 2 的幂），省略时为 256；
+/// * `idioms`：为 `true` 时用成语编码，范围从内置成语表中选取（如 `1, 8191`），`radix` 为心印成语表的条数，
+///   不能与基础字集、选字条件、形近字和同音字选项同时使用；
+/// * `stripPunctuation`：为 `true` 时规范化心印时去掉标点符号和空白。
+#[derive(Debug, Default)]
+struct SchemeOptions {
+    start: usize,
+    count: usize,
+    checksum: usize,
+    parity: usize,
+    salted: bool,
+    label: String,
+    charset: Option<String>,
+    charset_file: Option<String>,
+    filter: Option<String>,
+    confusable_free: bool,
+    homophone_free: bool,
+    ignore_tones: bool,
+    radix: Option<usize>,
+    idioms: bool,
+    strip_punctuation: bool,
+}
+
+impl SchemeOptions {
+    fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        if !options.is_object() {
+            return Err(invalid_options("options must be an object".to_string()));
+        }
+        let required = |key: &str| {
+            js_usize(options, key)?.ok_or_else(|| invalid_options(format!("`{}` is required", key)))
+        };
+        Ok(Self {
+            start: required("start")?,
+            count: required("count")?,
+            checksum: js_usize(options, "checksum")?.unwrap_or_default(),
+            parity: js_usize(options, "parity")?.unwrap_or_default(),
+            salted: js_bool(options, "salted")?,
+            label: js_string(options, "label")?.unwrap_or_default(),
+            charset: js_string(options, "charset")?,
+            charset_file: js_string(options, "charsetFile")?,
+            filter: js_string(options, "filter")?,
+            confusable_free: js_bool(options, "confusableFree")?,
+            homophone_free: js_bool(options, "homophoneFree")?,
+            ignore_tones: js_bool(options, "ignoreTones")?,
+            radix: js_usize(options, "radix")?,
+            idioms: js_bool(options, "idioms")?,
+            strip_punctuation: js_bool(options, "stripPunctuation")?,
+        })
     }
+
+    fn into_scheme(self) -> Result<Scheme, JsValue> {
+        let primary = match self.charset.as_deref() {
+            None => PrimaryCharset::default(),
+            Some("custom") => CustomCharset::parse(&self.charset_file.unwrap_or_default())
+                .map(PrimaryCharset::Custom)
+                .map_err(|err| error_to_js("parse charset file failed", &err))?,
+            Some(charset) => charset
+                .parse::<PrimaryCharset>()
+                .map_err(|err| error_to_js("parse charset failed", &err))?,
+        };
+        let filter = self
+            .filter
+            .map(|filter| filter.parse::<CharsetFilter>())
+            .transpose()
+            .map_err(|err| error_to_js("parse charset filter failed", &err))?;
+        let mut scheme = Scheme {
+            checksum: self.checksum,
+            parity: self.parity,
+            label: self.label,
+            primary,
+            filter,
+            confusable_free: self.confusable_free,
+            homophones: match (self.homophone_free, self.ignore_tones) {
+                (true, true) => Homophones::DistinctSyllables,
+                (true, false) => Homophones::DistinctTones,
+                (false, _) => Homophones::Allowed,
+            },
+            radix: self.radix.unwrap_or(256),
+            idioms: self.idioms,
+            strip_punctuation: self.strip_punctuation,
+            ..Scheme::new(SchemeVersion::LATEST, self.start, self.count)
+        };
+        if self.salted {
+            scheme = scheme
+                .with_random_salt()
+                .map_err(|err| error_to_js("generate salt failed", &err))?;
+        }
+        Ok(scheme)
+    }
+}
+
+fn invalid_options(msg: String) -> JsValue {
+    error_to_js("invalid scheme options", &Error::InvalidParams(msg))
+}
+
+/// 读取 JS 对象的属性，`undefined` 和 `null` 视为省略
+fn js_field(options: &JsValue, key: &str) -> Result<Option<JsValue>, JsValue> {
+    let value = Reflect::get(options, &key.into())?;
+    Ok((!value.is_undefined() && !value.is_null()).then_some(value))
+}
+
+fn js_usize(options: &JsValue, key: &str) -> Result<Option<usize>, JsValue> {
+    js_field(options, key)?
+        .map(|value| {
+            value
+                .as_f64()
+                .filter(|n| n.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(n))
+                .map(|n| n as usize)
+                .ok_or_else(|| invalid_options(format!("`{}` must be a non-negative integer", key)))
+        })
+        .transpose()
+}
+
+fn js_bool(options: &JsValue, key: &str) -> Result<bool, JsValue> {
+    js_field(options, key)?
+        .map(|value| {
+            value
+                .as_bool()
+                .ok_or_else(|| invalid_options(format!("`{}` must be a boolean", key)))
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+fn js_string(options: &JsValue, key: &str) -> Result<Option<String>, JsValue> {
+    js_field(options, key)?
+        .map(|value| {
+            value
+                .as_string()
+                .ok_or_else(|| invalid_options(format!("`{}` must be a string", key)))
+        })
+        .transpose()
+}
+
+/// 解析恢复卡（文本形式），使用自备字集时载入字集文件 `charset_file` 并核对 SHA256
+fn parse_card(card: &str, charset_file: Option<String>) -> Result<RecoveryCard, JsValue> {
+    let mut card = card
+        .parse::<RecoveryCard>()
+        .map_err(|err| error_to_js("parse recovery card failed", &err))?;
+    if let (PrimaryCharset::Custom(custom), Some(text)) = (&card.scheme.primary, charset_file) {
+        card.scheme.primary = custom
+            .load(&text)
+            .map(PrimaryCharset::Custom)
+            .map_err(|err| error_to_js("load charset file failed", &err))?;
+    }
+    Ok(card)
+}
+
+/// 用新的心印和方案参数重新加密恢复卡中的密钥，密钥可以是任意长度
+///
+/// `old_card` 为原来的恢复卡（文本形式），使用自备字集时需要提供字集文件的内容 `old_charset_file`；
+/// 新方案的参数 `options` 与 [`generate_xinyin_recovery_card`] 相同。
+/// 提供 `expected_address` 时先核对原来的恢复卡和心印还原出的密钥，地址不符时返回的 JS Error 的 `code`
+/// 为 `address_mismatch`，不会生成新的恢复卡；只能用于 32 字节的密钥。
+/// 返回 `{ card, address }`，`card` 为新恢复卡的文本形式；密钥为 32 字节时 `address` 为新旧恢复卡共同的钱包地址，
+/// 否则没有 `address`。
+#[wasm_bindgen]
+pub fn rekey_xinyin_recovery_card(
+    old_card: &str,
+    old_txt_in_heart: &str,
+    old_charset_file: Option<String>,
+    txt_in_heart: &str,
+    options: &JsValue,
+    expected_address: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let old_card = parse_card(old_card, old_charset_file)?;
+    let scheme = SchemeOptions::from_js(options)?.into_scheme()?;
+    let (words, secret) = rekey_words(
+        &old_card.words,
+        old_txt_in_heart,
+        &old_card.scheme,
+        txt_in_heart,
+        &scheme,
+        expected_address.as_deref(),
+    )
+    .map_err(|err| error_to_js("rekey recovery card failed", &err))?;
+
+    let result = js_sys::Object::new();
+    let _ = Reflect::set(
+        &result,
+        &"card".into(),
+        &RecoveryCard::new(scheme, words).to_string().into(),
+    );
+    if let Ok(secret_key) = <&[u8; 32]>::try_from(secret.as_slice()) {
+        let _ = Reflect::set(&result, &"address".into(), &address_of(secret_key).into());
+    }
+    Ok(result.into())
+}
+
+/// 用恢复卡（文本形式）和心印导入密钥，返回地址
+///
+/// 恢复卡使用自备字集时需要提供字集文件的内容 `charset_file`，与恢复卡记录的 SHA256 不符时
+/// 返回的 JS Error 的 `code` 为 `charset_mismatch`。
+#[wasm_bindgen]
+pub fn import_xinyin_recovery_card(
+    card: &str,
+    txt_in_heart: &str,
+    psw: &str,
+    charset_file: Option<String>,
+) -> Result<String, JsValue> {
+    let card = parse_card(card, charset_file)?;
+    let sk = import_recovery_card(&card, txt_in_heart)
+        .map_err(|err| error_to_js("import recovery card failed", &err))?;
+    let sk = <[u8; 32]>::try_from(sk.as_slice()).map_err(|_| {
+        error_to_js(
+            "import recovery card failed",
+            &Error::WrongLength("secret key must be 32 bytes".to_string()),
+        )
+    })?;
+
+    let encrypted_sk = EncryptedSk::encrypt_sk(&sk, psw, &WebSksStore)
+        .map_err(|err| error_to_js("encrypt sk failed", &err))?;
+
+    Ok(encrypted_sk.address.clone())
+}
+
+/// 把任意长度的密钥（如 64 字节的密钥对、16 字节的 BIP39 熵）编码为心印助记字
+#[wasm_bindgen]
+pub fn generate_xinyin_words(
+    txt_in_heart: &str,
+    start: usize,
+    count: usize,
+    checksum: usize,
+    secret: &[u8],
+    label: Option<String>,
+) -> Result<String, JsValue> {
+    let scheme = Scheme {
+        checksum,
+        label: label.unwrap_or_default(),
+        ..Scheme::new(SchemeVersion::LATEST, start, count)
+    };
+    generate_words(txt_in_heart, &scheme, secret)
+        .map_err(|err| error_to_js("generate words failed", &err))
+}
+
+/// 导入 [`generate_xinyin_words`] 生成的助记字，返回原始密钥，`checksum` 须与生成时相同
+#[wasm_bindgen]
+pub fn import_xinyin_words(
+    words: &str,
+    txt_in_heart: &str,
+    start: usize,
+    count: usize,
+    checksum: usize,
+    label: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let scheme = Scheme {
+        checksum,
+        label: label.unwrap_or_default(),
+        ..Scheme::v1(start, count)
+    };
+    import_words(words, txt_in_heart, &scheme)
+        .map_err(|err| error_to_js("import words failed", &err))
+}
+
+/// 整理输入的助记字：去掉分隔符，全角字符换成半角，繁体字、异体字换成规范字
+///
+/// 返回 `{ words, removed, replaced }`，`replaced` 为 `[{ position, from, to }]`，供页面提示用户核对；
+/// 导入函数会自动整理，无需先调用。
+#[wasm_bindgen]
+pub fn normalize_xinyin_words(words: &str) -> JsValue {
+    let normalized = normalize_words(words);
+    let replaced = Array::new();
+    for replacement in &normalized.replaced {
+        let item = js_sys::Object::new();
+        let _ = Reflect::set(
+            &item,
+            &"position".into(),
+            &(replacement.position as u32).into(),
+        );
+        let _ = Reflect::set(&item, &"from".into(), &replacement.from.to_string().into());
+        let _ = Reflect::set(&item, &"to".into(), &replacement.to.to_string().into());
+        replaced.push(&item);
+    }
+
+    let result = js_sys::Object::new();
+    let _ = Reflect::set(&result, &"words".into(), &normalized.words.as_str().into());
+    let _ = Reflect::set(
+        &result,
+        &"removed".into(),
+        &(normalized.removed as u32).into(),
+    );
+    let _ = Reflect::set(&result, &"replaced".into(), &replaced);
+    result.into()
+}
+
+/// 心印的规范形式（v3 起派生前先规范化），`strip_punctuation` 为 `true` 时去掉标点符号和空白
+#[wasm_bindgen]
+pub fn normalize_xinyin_heartprint(txt_in_heart: &str, strip_punctuation: Option<bool>) -> String {
+    normalize_heartprint(txt_in_heart, strip_punctuation.unwrap_or_default())
+}
+
+/// 与心印规范化后相同的几种常见写法，返回 `[{ description, text }]`，供页面提示用户换一种输入法也能还原
+#[wasm_bindgen]
+pub fn xinyin_heartprint_variants(txt_in_heart: &str, strip_punctuation: Option<bool>) -> Array {
+    let items = Array::new();
+    for (description, text) in
+        heartprint_variants(txt_in_heart, strip_punctuation.unwrap_or_default())
+    {
+        let item = js_sys::Object::new();
+        let _ = Reflect::set(&item, &"description".into(), &description.into());
+        let _ = Reflect::set(&item, &"text".into(), &text.as_str().into());
+        items.push(&item);
+    }
+    items
+}
+
+/// 用恢复卡（文本形式）中的方案参数计算心印指纹，生成和导入时都显示，供用户核对两次输入的是不是同一句心印
+///
+/// 返回 `{ text, words, colorEmoji, color, animalEmoji, animal }`，`text` 形如 `🟢🐼 潮映`。
+/// 恢复卡使用自备字集时需要提供字集文件的内容 `charset_file`。
+#[wasm_bindgen]
+pub fn xinyin_heartprint_fingerprint(
+    txt_in_heart: &str,
+    card: &str,
+    charset_file: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let card = parse_card(card, charset_file)?;
+    let fingerprint = heartprint_fingerprint(txt_in_heart, &card.scheme)
+        .map_err(|err| error_to_js("compute heartprint fingerprint failed", &err))?;
+
+    let result = js_sys::Object::new();
+    let _ = Reflect::set(&result, &"text".into(), &fingerprint.to_string().into());
+    let _ = Reflect::set(&result, &"words".into(), &fingerprint.words.as_str().into());
+    let _ = Reflect::set(
+        &result,
+        &"colorEmoji".into(),
+        &fingerprint.color_emoji.into(),
+    );
+    let _ = Reflect::set(&result, &"color".into(), &fingerprint.color.into());
+    let _ = Reflect::set(
+        &result,
+        &"animalEmoji".into(),
+        &fingerprint.animal_emoji.into(),
+    );
+    let _ = Reflect::set(&result, &"animal".into(), &fingerprint.animal.into());
+    Ok(result.into())
+}
+
+/// 估计心印的强度和按 Argon2 参数换算的破解成本，`argon2` 形如 `argon2id,m=19456,t=2,p=1`，省略时为默认参数
+///
+/// 返回 `{ entropyBits, guesses, guessesPerSecond, crackSeconds, crackCostUsd, level, warnings }`，
+/// `level` 为 `weak`、`fair` 或 `strong`，为 `weak` 时页面应拒绝使用这句心印；
+/// `warnings` 为 `[{ code, text }]`，`code` 为 `too_short`、`known_phrase`、`date` 或 `pattern`。
+#[wasm_bindgen]
+pub fn xinyin_heartprint_strength(
+    txt_in_heart: &str,
+    argon2: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let argon2 = argon2
+        .map(|argon2| argon2.parse::<Argon2Params>())
+        .transpose()
+        .map_err(|err| error_to_js("parse argon2 params failed", &err))?
+        .unwrap_or_default();
+    let strength = heartprint_strength(txt_in_heart, &argon2);
+
+    let warnings = Array::new();
+    for warning in &strength.warnings {
+        let item = js_sys::Object::new();
+        let text = match warning {
+            StrengthWarning::TooShort(len) => len.to_string(),
+            StrengthWarning::KnownPhrase(text)
+            | StrengthWarning::Date(text)
+            | StrengthWarning::Pattern(text) => text.clone(),
+        };
+        let _ = Reflect::set(&item, &"code".into(), &warning.code().into());
+        let _ = Reflect::set(&item, &"text".into(), &text.into());
+        warnings.push(&item);
+    }
+
+    let result = js_sys::Object::new();
+    let _ = Reflect::set(
+        &result,
+        &"entropyBits".into(),
+        &strength.entropy_bits.into(),
+    );
+    let _ = Reflect::set(&result, &"guesses".into(), &strength.guesses.into());
+    let _ = Reflect::set(
+        &result,
+        &"guessesPerSecond".into(),
+        &strength.guesses_per_second.into(),
+    );
+    let _ = Reflect::set(
+        &result,
+        &"crackSeconds".into(),
+        &strength.crack_seconds.into(),
+    );
+    let _ = Reflect::set(
+        &result,
+        &"crackCostUsd".into(),
+        &strength.crack_cost_usd.into(),
+    );
+    let _ = Reflect::set(&result, &"level".into(), &strength.level.as_str().into());
+    let _ = Reflect::set(&result, &"warnings".into(), &warnings);
+    Ok(result.into())
+}
+
+/// 助记字是否使用字表派生较弱的旧版方案（v1），为 `true` 时应提示用户迁移
+#[wasm_bindgen]
+pub fn is_weak_xinyin_words(words: &str) -> Result<bool, JsValue> {
+    SchemeVersion::detect(&normalize_words(words).words)
+        .map(|(version, _)| version.is_weak())
+        .map_err(|err| error_to_js("detect scheme version failed", &err))
 }
 
 struct WebSksStore;
 
 impl SksStore for WebSksStore {
-    fn save_encrypted_sk(&self, encrypted_sk_bs64: &str) -> anyhow::Result<()> {
+    fn save_encrypted_sk(&self, encrypted_sk_bs64: &str) -> xinyin::Result<()> {
         js_save_encrypted_sk_base64(encrypted_sk_bs64);
         Ok(())
     }
@@ -0,0 +1,134 @@
+#!/usr/bin/env python3
+"""生成 8105-meta.txt：《通用规范汉字表》中每个字的级别、笔画数、字频序号和常用词
+
+数据来源：
+- 级别：8105.bin 中的位置，前 3500 字为一级字，其后 3000 字为二级字，其余为三级字；
+- 笔画数：Perl 5.36.0 自带的 Unicode::Collate 1.31 中的 Unicode/Collate/CJK/Stroke.pm。
+  这个表与字表的笔画数不完全一致，按字表中同级字按笔画数排列的顺序拟合：
+  每级字的笔画数不递减，与 Stroke.pm 不同的字尽量少，相差 1、2 画的代价较小；
+- 字频：glibc 2.36 的 localedata/locales/iso14651_t1_pinyin，每行末尾 `#字频次` 中的频次；
+- 常用词：jieba-rs 0.7.4 的 src/data/dict.txt（jieba 词典），见 common_words()。
+
+用法：python3 scripts/charinfo.py path/to/Stroke.pm path/to/iso14651_t1_pinyin path/to/dict.txt > 8105-meta.txt
+"""
+
+import re
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+
+# 级别的边界：一级字 3500 个，二级字 3000 个
+LEVELS = [(0, 3500), (3500, 6500), (6500, 8105)]
+MAX_STROKES = 40
+
+# jieba 词典中的人名、地名、机构名等专名，不作为常用词
+NAMES = ("nr", "ns", "nrt", "nt", "nz", "nrfg")
+# 按词频选出的词不合适时手工指定
+WORDS = {
+    "末": "周末",
+}
+
+
+def stroke_table(path):
+    """Stroke.pm 中每个字的笔画数：`FDD0-28xx` 行之后的字为 xx 画"""
+    data = open(path, encoding="utf-8").read().split("__DATA__")[1].split("__END__")[0]
+    stroke, current = {}, None
+    for token in data.split():
+        m = re.match(r"FDD0-28([0-9A-F]{2})", token)
+        if m:
+            current = int(m.group(1), 16)
+            continue
+        stroke.setdefault(chr(int(token, 16)), current)
+    return stroke
+
+
+def fit_strokes(chars, stroke):
+    """按字表的顺序拟合笔画数，见文件说明"""
+
+    def cost(diff):
+        if diff == 0:
+            return 0
+        return 0.3 if abs(diff) == 1 else 0.6 if abs(diff) == 2 else 1
+
+    fit = {}
+    for lo, hi in LEVELS:
+        seq = chars[lo:hi]
+        dp = [0] * (MAX_STROKES + 1)
+        back = []
+        for ch in seq:
+            known = stroke.get(ch)
+            best, arg = float("inf"), 0
+            nd, nb = [0] * (MAX_STROKES + 1), [0] * (MAX_STROKES + 1)
+            for s in range(1, MAX_STROKES + 1):
+                if dp[s] < best:
+                    best, arg = dp[s], s
+                nd[s] = best + (0 if known is None else cost(known - s))
+                nb[s] = arg
+            dp = nd
+            back.append(nb)
+        s = min(range(1, MAX_STROKES + 1), key=lambda x: dp[x])
+        values = []
+        for b in reversed(back):
+            values.append(s)
+            s = b[s]
+        values.reverse()
+        fit.update(zip(seq, values))
+    return fit
+
+
+def frequency_rank(path, chars):
+    """按 iso14651_t1_pinyin 中的频次排序，1 为最常用；频次相同或没有统计到的字按字表的顺序"""
+    freq = {}
+    for line in open(path, encoding="utf-8"):
+        m = re.match(r"<U([0-9A-F]+)> .*#.(\d+)$", line.strip())
+        if m:
+            ch = chr(int(m.group(1), 16))
+            freq[ch] = max(freq.get(ch, 0), int(m.group(2)))
+    ranked = sorted(chars, key=lambda ch: (-freq.get(ch, 0), chars.index(ch)))
+    return {ch: i + 1 for i, ch in enumerate(ranked)}
+
+
+def common_words(path, level):
+    """每个字选一个含这个字的常用词，口述时区分同音字
+
+    优先选两字词、不是专名、另一个字是一级字的词，条件相同时选词频最高的。
+    """
+    best = {}
+    for line in open(path, encoding="utf-8"):
+        word, freq, *tag = line.split()
+        freq = int(freq)
+        if len(word) not in (2, 3) or any(ch not in level for ch in word) or len(set(word)) != len(word):
+            continue
+        tier = (len(word) == 2) * 2 + (not (tag and tag[0] in NAMES))
+        for ch in set(word):
+            common = all(level[other] == 1 for other in word if other != ch)
+            key = (tier, common, freq)
+            if ch not in best or key > best[ch][0]:
+                best[ch] = (key, word)
+    words = {ch: word for ch, (_, word) in best.items()}
+    words.update(WORDS)
+    return words
+
+
+def main(stroke_pm, iso14651, jieba_dict):
+    chars = (ROOT / "8105.bin").read_text(encoding="utf-8").strip()
+    level = {}
+    for n, (lo, hi) in enumerate(LEVELS):
+        level.update((ch, n + 1) for ch in chars[lo:hi])
+    strokes = fit_strokes(chars, stroke_table(stroke_pm))
+    rank = frequency_rank(iso14651, chars)
+    words = common_words(jieba_dict, level)
+
+    print("# 通用规范汉字表的字信息：每行依次为字、级别（1-3 级字表）、笔画数、字频序号（1 为最常用）、含这个字的常用词（口述时区分同音字，没有时省略）")
+    print("# 笔画数依据 Unicode::Collate 1.31 的 Stroke.pm 按字表中同级字的笔画顺序拟合；字频据 glibc 2.36 的 iso14651_t1_pinyin，没有统计到的字排在最后；常用词取自 jieba-rs 0.7.4 的词典")
+    print("# 由 scripts/charinfo.py 生成，行的顺序与 8105.bin 相同。以 # 开头的行为注释")
+    for ch in chars:
+        fields = [ch, str(level[ch]), str(strokes[ch]), str(rank[ch])]
+        if ch in words:
+            fields.append(words[ch])
+        print(" ".join(fields))
+
+
+if __name__ == "__main__":
+    main(*sys.argv[1:4])
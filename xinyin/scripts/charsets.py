@@ -0,0 +1,42 @@
+#!/usr/bin/env python3
+"""生成内置的其它基础字集：gb2312-1.txt、big5-common.txt、jis-1.txt
+
+数据来源：Perl 5.36.0 自带的 Unicode::Collate 1.31 中的 Unicode/Collate/CJK/{GB2312,Big5,JISX0208}.pm，
+每个文件按编码顺序列出字集中的汉字：
+- GB2312.pm：前 3755 字为 GB 2312 一级汉字，按拼音排列；
+- Big5.pm：开头 9 个是符号区的度量衡单位字（兙兛兞兝兡兣嗧瓩糎），其后 5401 字为常用字，按笔画排列；
+- JISX0208.pm：前 2965 字为 JIS X 0208 第一水准汉字，按音读排列。
+
+用法：python3 scripts/charsets.py gb2312|big5|jis path/to/Unicode/Collate/CJK > gb2312-1.txt
+"""
+
+import sys
+from pathlib import Path
+
+# 字集名称：Unicode::Collate 的文件名、跳过的字数、字数、说明
+CHARSETS = {
+    "gb2312": ("GB2312", 0, 3755, "GB 2312 一级汉字 3755 字，按拼音排列"),
+    "big5": ("Big5", 9, 5401, "Big5 常用字 5401 字，按笔画排列"),
+    "jis": ("JISX0208", 0, 2965, "JIS X 0208 第一水准汉字 2965 字，按音读排列"),
+}
+
+# 每行的字数
+LINE_LEN = 50
+
+
+def main(name, collate_dir):
+    module, skip, count, description = CHARSETS[name]
+    data = (Path(collate_dir) / f"{module}.pm").read_text(encoding="utf-8")
+    data = data.split("__DATA__")[1].split("__END__")[0]
+    chars = [chr(int(cp, 16)) for cp in data.split()][skip : skip + count]
+    assert len(chars) == len(set(chars)) == count
+
+    print(f"# {description}")
+    print(f"# 由 scripts/charsets.py 从 Perl 5.36.0 自带的 Unicode::Collate 1.31 的 {module}.pm 生成")
+    print("# 字集为其余各行的字按顺序连接，以 # 开头的行为注释")
+    for i in range(0, count, LINE_LEN):
+        print("".join(chars[i : i + LINE_LEN]))
+
+
+if __name__ == "__main__":
+    main(*sys.argv[1:3])
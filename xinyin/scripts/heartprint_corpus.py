@@ -0,0 +1,58 @@
+#!/usr/bin/env python3
+"""更新 heartprint-corpus.txt：心印强度估计用的常见语料
+
+语料分为四节，以 `# ` 开头的节标题分隔：
+- 诗词名句、经典和流行语录，常用语、祝福语和口号，常见密码：手工整理，直接在 heartprint-corpus.txt 中编辑；
+- jieba 词典中标为习用语（词性 `l`）、词频不低于 100、不少于 3 个字的词：
+  由这个脚本从 jieba-rs 0.7.4 的 src/data/dict.txt 重新生成，按词典中的顺序排列。
+
+每句只保留第一次出现的位置。脚本读入现有的语料文件，保留手工整理的各节，重新生成 jieba 一节。
+
+用法：python3 scripts/heartprint_corpus.py path/to/dict.txt heartprint-corpus.txt > heartprint-corpus.new
+"""
+
+import sys
+
+HEADER = [
+    "# 心印强度估计用的常见语料：心印中出现这些句子时，按语料的条数而不是字数估计熵",
+    "# 每行一句，不含标点；以 # 开头的行为注释。成语另见 idioms.txt",
+    "# 手工整理的各节直接在本文件中编辑，jieba 一节由 scripts/heartprint_corpus.py 从 jieba-rs 0.7.4 的 jieba 词典生成",
+]
+SECTIONS = [
+    "# 诗词名句、经典和流行语录",
+    "# 常用语、祝福语和口号",
+    "# jieba 词典中标为习用语、词频不低于 100 的词",
+    "# 常见密码，不区分大小写",
+]
+JIEBA = SECTIONS[2]
+
+
+def main(jieba_dict, corpus):
+    sections, current = {}, None
+    for line in open(corpus, encoding="utf-8"):
+        line = line.strip()
+        if line in SECTIONS:
+            current = line
+            sections[current] = []
+        elif current and line and not line.startswith("#"):
+            sections[current].append(line)
+
+    sections[JIEBA] = []
+    for line in open(jieba_dict, encoding="utf-8"):
+        word, freq, *tag = line.split()
+        if tag and tag[0] == "l" and int(freq) >= 100 and len(word) >= 3:
+            sections[JIEBA].append(word)
+
+    seen = set()
+    for line in HEADER:
+        print(line)
+    for title in SECTIONS:
+        print(title)
+        for line in sections[title]:
+            if line not in seen:
+                seen.add(line)
+                print(line)
+
+
+if __name__ == "__main__":
+    main(*sys.argv[1:3])
@@ -0,0 +1,43 @@
+#!/usr/bin/env python3
+"""生成 heartprint-words.txt：心印强度估计用的词频表
+
+数据来源：jieba-rs 0.7.4 的 src/data/dict.txt（jieba 词典）。收录《通用规范汉字表》中有词频的单字，
+和全部由规范字组成、最常用的 8000 个二至四字词，按词频排列；`# total` 行为整个 jieba 词典的总词频。
+
+用法：python3 scripts/heartprint_words.py path/to/dict.txt > heartprint-words.txt
+"""
+
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+
+WORD_COUNT = 8000
+MAX_WORD_LEN = 4
+
+
+def main(jieba_dict):
+    chars = set((ROOT / "8105.bin").read_text(encoding="utf-8").strip())
+    total, single, multi = 0, {}, {}
+    for line in open(jieba_dict, encoding="utf-8"):
+        word, freq, *_ = line.split()
+        freq = int(freq)
+        total += freq
+        if len(word) <= MAX_WORD_LEN and all(ch in chars for ch in word):
+            words = single if len(word) == 1 else multi
+            words[word] = max(words.get(word, 0), freq)
+    by_freq = lambda item: (-item[1], item[0])
+    rows = sorted(single.items(), key=by_freq)
+    rows += sorted(multi.items(), key=by_freq)[:WORD_COUNT]
+    rows.sort(key=by_freq)
+
+    print(f"# 心印强度估计用的词频表：《通用规范汉字表》中的单字和最常用的 {WORD_COUNT} 个多字词，按词频排列")
+    print("# 由 scripts/heartprint_words.py 从 jieba-rs 0.7.4 的 jieba 词典生成，每行为词和词频，`# total` 行为 jieba 词典的总词频")
+    print("# 以 # 开头的其余行为注释")
+    print(f"# total {total}")
+    for word, freq in rows:
+        print(word, freq)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
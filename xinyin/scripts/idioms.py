@@ -0,0 +1,42 @@
+#!/usr/bin/env python3
+"""生成 idioms.txt：成语编码用的 8192 条四字成语
+
+数据来源：jieba-rs 0.7.4 的 src/data/dict.txt（jieba 词典）中词性标为 `i`（成语）的四字词，
+只收录全部由《通用规范汉字表》中的字组成的成语。先按词频、再按所含最生僻字的字频序号
+（8105-meta.txt 的第 4 列，见 charinfo.py）排序，取前 8192 条。
+
+用法：python3 scripts/idioms.py path/to/dict.txt > idioms.txt
+"""
+
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+
+COUNT = 8192
+
+
+def main(jieba_dict):
+    rank = {}
+    for line in open(ROOT / "8105-meta.txt", encoding="utf-8"):
+        if line.strip() and not line.startswith("#"):
+            fields = line.split()
+            rank[fields[0]] = int(fields[3])
+
+    idioms = {}
+    for line in open(jieba_dict, encoding="utf-8"):
+        word, freq, *tag = line.split()
+        if tag and tag[0] == "i" and len(word) == 4 and all(ch in rank for ch in word):
+            idioms[word] = max(idioms.get(word, 0), int(freq))
+    ranked = sorted(idioms, key=lambda w: (-idioms[w], max(rank[ch] for ch in w), w))[:COUNT]
+    assert len(ranked) == COUNT
+
+    print(f"# 成语表：{COUNT} 条四字成语，均由《通用规范汉字表》中的字组成，按常用程度排列")
+    print("# 由 scripts/idioms.py 从 jieba-rs 0.7.4 的 jieba 词典中标为成语的词生成，先按词频、再按所含最生僻字的字频排序")
+    print("# 以 # 开头的行为注释")
+    for idiom in ranked:
+        print(idiom)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -0,0 +1,64 @@
+#!/usr/bin/env python3
+"""生成 pinyin.txt：《通用规范汉字表》中每个字的带调拼音
+
+数据来源：pinyin-data v0.13.0（https://github.com/mozillazg/pinyin-data，MIT 许可）的 pinyin.txt，
+随 crates.io 上的 pinyin 0.10.0 发布（包内路径 pinyin-data/pinyin.txt）。
+pinyin-data 综合了 Unihan 的 kHanyuPinyin、kXHC1983、kMandarin 等字段，每个字最常用的读音在前。
+
+用法：python3 scripts/pinyin.py path/to/pinyin-data/pinyin.txt > pinyin.txt
+"""
+
+import re
+import sys
+import unicodedata
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+
+# pinyin-data 的第一个读音不是最常用读音的字，按《现代汉语词典》第 7 版调整
+PRIMARY = {
+    "犸": "mǎ",
+}
+
+TONE_MARKS = {"̄": "1", "́": "2", "̌": "3", "̀": "4"}
+
+
+def numbered(pinyin):
+    """把带声调符号的拼音换成数字声调，如 `lüè` 为 `lve4`；轻声为 5，无法用字母输入的 ê 返回 None"""
+    letters, tone = [], "5"
+    for ch in unicodedata.normalize("NFD", pinyin):
+        if ch in TONE_MARKS:
+            tone = TONE_MARKS[ch]
+        elif ch == "̈":
+            letters[-1] = "v"
+        elif "a" <= ch <= "z":
+            letters.append(ch)
+        else:
+            return None
+    return "".join(letters) + tone
+
+
+def main(source):
+    readings = {}
+    for line in open(source, encoding="utf-8"):
+        m = re.match(r"U\+([0-9A-F]+): (\S+)", line)
+        if m:
+            readings[chr(int(m.group(1), 16))] = m.group(2).split(",")
+
+    print("# 拼音表：每行为《通用规范汉字表》中的一个字和它的全部带调拼音，最常用的读音在前，行的顺序与 8105.bin 相同")
+    print("# 声调用数字 1-4 表示，5 为轻声，v 表示 ü。由 scripts/pinyin.py 从 pinyin-data v0.13.0 生成")
+    print("# 以 # 开头的行为注释")
+    for ch in (ROOT / "8105.bin").read_text(encoding="utf-8").strip():
+        pinyins = readings[ch]
+        if ch in PRIMARY:
+            pinyins = [PRIMARY[ch]] + [p for p in pinyins if p != PRIMARY[ch]]
+        out = []
+        for pinyin in pinyins:
+            pinyin = numbered(pinyin)
+            if pinyin and pinyin not in out:
+                out.append(pinyin)
+        print(ch, " ".join(out))
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -0,0 +1,63 @@
+#!/usr/bin/env python3
+"""生成 unicode-data.txt：心印规范化（NFC 和去掉标点）所需的 Unicode 数据
+
+数据来源：Unicode 14.0 的字符数据库，取自 Python 3.11 的 unicodedata 模块（unicodedata.unidata_version 为 14.0.0）。
+用其它版本的 Python 生成时 Unicode 版本可能不同，而心印的规范化结果必须保持不变，脚本会检查版本。
+
+用法：python3 scripts/unicode_data.py > unicode-data.txt
+"""
+
+import unicodedata as u
+
+UNICODE_VERSION = "14.0.0"
+
+# 谚文音节按算法分解，不列入分解表
+HANGUL_SYLLABLES = range(0xAC00, 0xD7A4)
+
+
+def ranges(pred):
+    """`pred` 的值相同且不为假的连续码位区间：起、止和值"""
+    out, start, value = [], None, None
+    for cp in range(0x110000 + 1):
+        v = pred(cp) if cp < 0x110000 else None
+        if start is not None and v != value:
+            out.append((start, cp - 1, value))
+            start = None
+        if start is None and v:
+            start, value = cp, v
+    return out
+
+
+def main():
+    assert u.unidata_version == UNICODE_VERSION, u.unidata_version
+
+    decompositions, exclusions = [], []
+    for cp in range(0x110000):
+        if cp in HANGUL_SYLLABLES:
+            continue
+        ch = chr(cp)
+        decomposition = u.decomposition(ch)
+        if not decomposition or decomposition.startswith("<"):
+            continue
+        parts = decomposition.split()
+        decompositions.append("D %X %s" % (cp, " ".join(parts)))
+        composed = u.normalize("NFC", "".join(chr(int(p, 16)) for p in parts))
+        if len(parts) == 2 and composed != ch:
+            exclusions.append("X %X" % cp)
+    combining = ["C %X %X %d" % r for r in ranges(lambda cp: u.combining(chr(cp)))]
+    punctuation = [
+        "P %X %X" % (start, end)
+        for start, end, _ in ranges(lambda cp: u.category(chr(cp)).startswith("P"))
+    ]
+
+    print("# 心印规范化所需的 Unicode 数据（Unicode 14.0），由 scripts/unicode_data.py 用 Python 3.11 的 unicodedata 生成，码位均为十六进制")
+    print("# D 字 分解：一层规范分解（不含谚文音节，谚文按算法分解）")
+    print("# X 字：有两字规范分解、但不参与组合的字")
+    print("# C 起 止 组合类：组合类非 0 的区间")
+    print("# P 起 止：标点符号（一般类别 P*）的区间")
+    for line in decompositions + exclusions + combining + punctuation:
+        print(line)
+
+
+if __name__ == "__main__":
+    main()
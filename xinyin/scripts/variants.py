@@ -0,0 +1,96 @@
+#!/usr/bin/env python3
+"""生成 variants.txt：繁体字、异体字和兼容字形对应的规范字
+
+数据来源：
+- glibc 2.36 的 localedata/locales/translit_cjk_variants：其中的字形关系不分方向，
+  只与一个规范字（8105.bin 中的字）相连的字形归入这个规范字；
+  与多个规范字相连的字形按 AMBIGUOUS 手工指定，没有指定的不收录；
+- 康熙部首、CJK 兼容汉字等兼容字形：按 Unicode 14.0 的 NFKC 规范化（Python 3.11 的 unicodedata）
+  得到的字归入对应的规范字。
+
+用法：python3 scripts/variants.py path/to/translit_cjk_variants > variants.txt
+"""
+
+import collections
+import re
+import sys
+import unicodedata
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+
+# 与多个规范字相连的字形：每项为字形和它对应的规范字
+AMBIGUOUS = """
+椏桠 弎三 丟丢 苅刈 廼乃 麼么 尛么 庅么 書书 暸了 亐亏 伝传 働动 動动 讎雠 仮假 伱你 儞你 妳你 徣借 勊克 尅克
+笧册 悽凄 剗铲 剷铲 鏟铲 勑敕 曻升 阩升 協协 旪叶 槕桌 蔔卜 卥西 滷卤 瀂卤 壥廛 鄽廛 発发 綴缀 枱台 咊和 惒和
+啠哲 嚞哲 埑哲 悊哲 囉啰 憘喜 歖喜 甼町 圽没 歾殁 歿殁 堯尧 尭尧 嵜崎 碕崎 掃扫 隁堰 復复 學学 斅学 斆学 斈学
+寍宁 寕宁 寗宁 寜宁 寧宁 虵蛇 寉鹤 靍鹤 靎鹤 靏鹤 鶴鹤 鸖鹤 帰归 歸归 辧辨 辯辩 徴征 徑径 逕径 懴忏 炤照 燳照
+瞾曌 缼缺 濱滨 濵滨 浧澄 瀓澄 豀溪 渓溪 粬曲 麯曲 麴曲 鎔熔 煬炀 鍛锻 鏡镜 瑠琉 璢琉 璡琎 穌苏 蘇苏 癰痈 秇艺
+萟艺 蓺艺 藝艺 鞦秋 稁稿 稾稿 藳稿 縲缧 絆绊 絤线 綫线 線线 繰缲 鑵罐 膽胆 躍跃 躊踌 鍾钟 鎡镃 風风 聖圣 咲笑
+抂狂 陁陀
+""".split()
+
+# 康熙部首、CJK 兼容汉字和 CJK 兼容汉字补充
+COMPATIBILITY = [
+    *range(0x2E80, 0x2FE0),
+    *range(0xF900, 0xFB00),
+    *range(0x2F800, 0x2FA20),
+]
+
+
+def main(translit):
+    order = (ROOT / "8105.bin").read_text(encoding="utf-8").strip()
+    std = set(order)
+
+    related = collections.defaultdict(set)
+    for line in open(translit, encoding="utf-8"):
+        m = re.match(r"<U([0-9A-F]+)> (.*)", line)
+        if not m:
+            continue
+        a = chr(int(m.group(1), 16))
+        for target in re.findall(r"<U([0-9A-F]+)>", m.group(2)):
+            b = chr(int(target, 16))
+            related[a].add(b)
+            related[b].add(a)
+
+    mapping, ambiguous = {}, {}
+    for variant, chars in related.items():
+        if variant in std:
+            continue
+        targets = {ch for ch in chars if ch in std}
+        if len(targets) == 1:
+            mapping[variant] = next(iter(targets))
+        elif targets:
+            ambiguous[variant] = targets
+
+    for cp in COMPATIBILITY:
+        ch = chr(cp)
+        nfkc = unicodedata.normalize("NFKC", ch)
+        if len(nfkc) == 1 and nfkc != ch and nfkc in std:
+            mapping.setdefault(ch, nfkc)
+
+    for pair in AMBIGUOUS:
+        variant, target = pair
+        assert target in ambiguous.get(variant, ()), pair
+        mapping[variant] = target
+
+    # 兼容字形规范化后是繁体字、异体字时，归入同一个规范字
+    for cp in COMPATIBILITY:
+        ch = chr(cp)
+        nfkc = unicodedata.normalize("NFKC", ch)
+        if len(nfkc) == 1 and nfkc != ch and nfkc not in std and nfkc in mapping:
+            mapping.setdefault(ch, mapping[nfkc])
+
+    variants = collections.defaultdict(list)
+    for variant, target in mapping.items():
+        variants[target].append(variant)
+
+    print("# 繁体字、异体字表：每行第一个字取自《通用规范汉字表》，其后是它的繁体字、异体字和康熙部首等兼容字形")
+    print("# 由 scripts/variants.py 从 glibc 2.36 的 translit_cjk_variants 和 Unicode 14.0 的 NFKC 规范化生成，只收录能唯一对应到一个规范字的字形")
+    print("# 以 # 开头的行为注释")
+    for target in sorted(variants, key=order.index):
+        print(target, "".join(sorted(variants[target])))
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
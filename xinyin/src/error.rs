@@ -0,0 +1,266 @@
+use std::fmt;
+
+use crate::xinyin::{MIN_RANGE_COUNT, RANGE_MARGIN};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// 心印的错误类型
+///
+/// [`Error::code`] 返回稳定的错误码，界面据此决定提示内容，不要匹配错误信息文本。
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// 基础字集范围不合法
+    InvalidRange {
+        start: usize,
+        count: usize,
+        /// 基础字集的字数
+        len: usize,
+    },
+    /// 参数不合法，如校验字、纠错字个数或 Argon2 参数
+    InvalidParams(String),
+    /// 恢复卡、存储记录等格式不正确
+    InvalidFormat(String),
+    /// 助记字或密钥的长度不对
+    WrongLength(String),
+    /// 不支持的方案版本
+    UnsupportedVersion(String),
+    /// 助记字中有字不在心印字表里
+    UnknownCharacters(Vec<UnknownCharacter>),
+    /// 成语编码的助记字中有成语不在心印成语表里
+    UnknownIdioms(Vec<UnknownIdiom>),
+    /// 拼音在心印字表中找不到对应的字
+    UnknownPinyin(Vec<PinyinSyllable>),
+    /// 拼音对应心印字表中的多个字，校验字也无法确定是哪一个
+    AmbiguousPinyin(Vec<PinyinSyllable>),
+    /// 抄错的字太多，纠错字无法纠正
+    Uncorrectable(String),
+    /// 校验字与助记字不符，助记字中有抄错的字
+    ChecksumMismatch,
+    /// 还原出的私钥与预期的钱包地址不符，通常是心印或助记字有误
+    AddressMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// 重新加密后的助记字还原出的密钥与原来的不同
+    SecretMismatch,
+    /// 自备字集文件与恢复卡中记录的 SHA256 不符
+    CharsetMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// 用于合并的份数少于门限
+    NotEnoughShares {
+        threshold: usize,
+        got: usize,
+    },
+    /// Argon2 派生密钥失败
+    KdfFailure(String),
+    EncryptFailure(String),
+    /// 解密失败，通常是密码错误
+    DecryptFailure(String),
+    /// 系统随机数生成失败
+    RandomFailure(String),
+    /// 读写密钥存储失败
+    StoreFailure(String),
+    /// 找不到地址对应的密钥
+    NotFound(String),
+    SignFailure(String),
+}
+
+/// 助记字中不在心印字表里的字
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCharacter {
+    /// 从 0 开始的位置，不含版本标记字
+    pub position: usize,
+    pub ch: char,
+    /// 心印字表中与之形近的字
+    pub suggestions: Vec<char>,
+}
+
+/// 成语编码的助记字中不在心印成语表里的成语
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownIdiom {
+    /// 从 0 开始的成语序号，不含版本标记字
+    pub position: usize,
+    pub idiom: String,
+    /// 心印成语表中与之只差一个字的成语
+    pub suggestions: Vec<String>,
+}
+
+/// 按拼音输入的助记字中的一个音节
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinyinSyllable {
+    /// 从 0 开始的位置，不含版本标记字
+    pub position: usize,
+    /// 输入的拼音
+    pub syllable: String,
+    /// 心印字表中读这个音的字，找不到时为空
+    pub candidates: Vec<char>,
+}
+
+impl Error {
+    /// 稳定的错误码，如 `checksum_mismatch`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::InvalidRange { .. } => "invalid_range",
+            Error::InvalidParams(_) => "invalid_params",
+            Error::InvalidFormat(_) => "invalid_format",
+            Error::WrongLength(_) => "wrong_length",
+            Error::UnsupportedVersion(_) => "unsupported_version",
+            Error::UnknownCharacters(_) => "unknown_characters",
+            Error::UnknownIdioms(_) => "unknown_idioms",
+            Error::UnknownPinyin(_) => "unknown_pinyin",
+            Error::AmbiguousPinyin(_) => "ambiguous_pinyin",
+            Error::Uncorrectable(_) => "uncorrectable",
+            Error::ChecksumMismatch => "checksum_mismatch",
+            Error::AddressMismatch { .. } => "address_mismatch",
+            Error::SecretMismatch => "secret_mismatch",
+            Error::CharsetMismatch { .. } => "charset_mismatch",
+            Error::NotEnoughShares { .. } => "not_enough_shares",
+            Error::KdfFailure(_) => "kdf_failure",
+            Error::EncryptFailure(_) => "encrypt_failure",
+            Error::DecryptFailure(_) => "decrypt_failure",
+            Error::RandomFailure(_) => "random_failure",
+            Error::StoreFailure(_) => "store_failure",
+            Error::NotFound(_) => "not_found",
+            Error::SignFailure(_) => "sign_failure",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidRange { start, count, len } => write!(
+                f,
+                "invalid charset range({},{}): start must be between 1 and {}, count must be greater than {} and start + count must not exceed {}",
+                start,
+                count,
+                len.saturating_sub(RANGE_MARGIN),
+                MIN_RANGE_COUNT,
+                len
+            ),
+            Error::InvalidParams(msg) => write!(f, "invalid params: {}", msg),
+            Error::InvalidFormat(msg) => write!(f, "invalid format: {}", msg),
+            Error::WrongLength(msg) => write!(f, "wrong length: {}", msg),
+            Error::UnsupportedVersion(msg) => write!(f, "unsupported scheme version: {}", msg),
+            Error::UnknownCharacters(unknowns) => {
+                write!(f, "words not found in 256-words:")?;
+                for unknown in unknowns {
+                    write!(f, " `{}` at {}", unknown.ch, unknown.position)?;
+                    if !unknown.suggestions.is_empty() {
+                        let suggestions = unknown.suggestions.iter().collect::<String>();
+                        write!(f, " (did you mean one of `{}`?)", suggestions)?;
+                    }
+                    write!(f, ";")?;
+                }
+                Ok(())
+            }
+            Error::UnknownIdioms(unknowns) => {
+                write!(f, "idioms not found in the idiom table:")?;
+                for unknown in unknowns {
+                    write!(f, " `{}` at {}", unknown.idiom, unknown.position)?;
+                    if !unknown.suggestions.is_empty() {
+                        let suggestions = unknown.suggestions.join("`, `");
+                        write!(f, " (did you mean one of `{}`?)", suggestions)?;
+                    }
+                    write!(f, ";")?;
+                }
+                Ok(())
+            }
+            Error::UnknownPinyin(syllables) => {
+                write!(f, "pinyin not found in 256-words:")?;
+                for syllable in syllables {
+                    write!(f, " `{}` at {};", syllable.syllable, syllable.position)?;
+                }
+                Ok(())
+            }
+            Error::AmbiguousPinyin(syllables) => {
+                write!(f, "ambiguous pinyin:")?;
+                for syllable in syllables {
+                    let candidates = syllable.candidates.iter().collect::<String>();
+                    write!(
+                        f,
+                        " `{}` at {} (one of `{}`);",
+                        syllable.syllable, syllable.position, candidates
+                    )?;
+                }
+                Ok(())
+            }
+            Error::Uncorrectable(msg) => write!(f, "too many errors to correct: {}", msg),
+            Error::ChecksumMismatch => write!(
+                f,
+                "checksum mismatch: some characters were miscopied, check the words against the backup"
+            ),
+            Error::AddressMismatch { expected, actual } => write!(
+                f,
+                "address mismatch: expected {}, but got {}; heartprint or characters wrong",
+                expected, actual
+            ),
+            Error::SecretMismatch => {
+                write!(
+                    f,
+                    "secret mismatch: new words do not decode to the same secret"
+                )
+            }
+            Error::CharsetMismatch { expected, actual } => write!(
+                f,
+                "charset mismatch: expected sha256 {}, but the file is {}",
+                expected, actual
+            ),
+            Error::NotEnoughShares { threshold, got } => write!(
+                f,
+                "not enough shares: {} required, but got {}",
+                threshold, got
+            ),
+            Error::KdfFailure(msg) => write!(f, "failed to derive key: {}", msg),
+            Error::EncryptFailure(msg) => write!(f, "failed to encrypt: {}", msg),
+            Error::DecryptFailure(msg) => write!(f, "failed to decrypt: {}", msg),
+            Error::RandomFailure(msg) => write!(f, "failed to generate random bytes: {}", msg),
+            Error::StoreFailure(msg) => write!(f, "store failure: {}", msg),
+            Error::NotFound(msg) => write!(f, "not found: {}", msg),
+            Error::SignFailure(msg) => write!(f, "failed to sign: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_unknown_characters() {
+        let err = Error::UnknownCharacters(vec![
+            UnknownCharacter {
+                position: 5,
+                ch: '训',
+                suggestions: vec![],
+            },
+            UnknownCharacter {
+                position: 11,
+                ch: '刀',
+                suggestions: vec!['力'],
+            },
+        ]);
+        assert_eq!(err.code(), "unknown_characters");
+        assert_eq!(
+            err.to_string(),
+            "words not found in 256-words: `训` at 5; `刀` at 11 (did you mean one of `力`?);"
+        );
+    }
+    #[test]
+    fn test_display_invalid_range() {
+        let err = Error::InvalidRange {
+            start: 8000,
+            count: 100,
+            len: 8105,
+        };
+        assert_eq!(
+            err.to_string(),
+            "invalid charset range(8000,100): start must be between 1 and 7600, count must be greater than 500 and start + count must not exceed 8105"
+        );
+    }
+}
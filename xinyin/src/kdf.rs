@@ -0,0 +1,168 @@
+use crate::{Error, Result};
+use argon2::{Argon2, Params, Version};
+use std::{fmt, str::FromStr};
+
+pub use argon2::Algorithm as Argon2Algorithm;
+
+/// Argon2 参数
+///
+/// 默认值与 `Argon2::default()` 相同（argon2id，m=19456，t=2，p=1），
+/// 非默认参数必须和备份一起保存，否则无法解密。
+/// 文本形式为 `argon2id,m=19456,t=2,p=1`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Params {
+    pub algorithm: Argon2Algorithm,
+    /// 内存开销，单位 KiB
+    pub m_cost: u32,
+    /// 迭代次数
+    pub t_cost: u32,
+    /// 并行度
+    pub p_cost: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Self {
+            algorithm: Argon2Algorithm::default(),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// 内存开销的上限：1 GiB，单位 KiB
+pub const MAX_M_COST: u32 = 1024 * 1024;
+/// 迭代次数的上限
+pub const MAX_T_COST: u32 = 64;
+/// 并行度的上限
+pub const MAX_P_COST: u32 = 16;
+
+impl Argon2Params {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 检查参数是否合法且不超过上限
+    ///
+    /// 参数来自恢复卡或存储记录时不可信，过大的参数会让一次派生耗尽内存或长时间无响应。
+    pub fn validate(&self) -> Result<()> {
+        if self.m_cost > MAX_M_COST || self.t_cost > MAX_T_COST || self.p_cost > MAX_P_COST {
+            return Err(Error::InvalidParams(format!(
+                "argon2 params {}: m must not exceed {}, t must not exceed {} and p must not exceed {}",
+                self, MAX_M_COST, MAX_T_COST, MAX_P_COST
+            )));
+        }
+        Params::new(self.m_cost, self.t_cost, self.p_cost, None)
+            .map_err(|err| Error::InvalidParams(format!("argon2 params {}: {}", self, err)))?;
+        Ok(())
+    }
+
+    /// 派生 `out.len()` 字节的密钥
+    #[cfg_attr(
+        not(any(feature = "generate", feature = "import", feature = "sks")),
+        allow(dead_code)
+    )]
+    pub(crate) fn hash_password_into(&self, pwd: &[u8], salt: &[u8], out: &mut [u8]) -> Result<()> {
+        self.validate()?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(out.len()))
+            .map_err(|err| Error::InvalidParams(format!("argon2 params {}: {}", self, err)))?;
+
+        Argon2::new(self.algorithm, Version::default(), params)
+            .hash_password_into(pwd, salt, out)
+            .map_err(|err| Error::KdfFailure(format!("argon2: {}", err)))
+    }
+}
+
+impl fmt::Display for Argon2Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},m={},t={},p={}",
+            self.algorithm, self.m_cost, self.t_cost, self.p_cost
+        )
+    }
+}
+
+impl FromStr for Argon2Params {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.trim().split(',');
+
+        let algorithm = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<Argon2Algorithm>()
+            .map_err(|_| Error::InvalidParams(format!("invalid argon2 algorithm in `{}`", s)))?;
+
+        let mut params = Self {
+            algorithm,
+            ..Self::default()
+        };
+        for part in parts {
+            let invalid = || Error::InvalidParams(format!("invalid argon2 param `{}`", part));
+            let (key, value) = part.split_once('=').ok_or_else(invalid)?;
+            let value = value.trim().parse::<u32>().map_err(|_| invalid())?;
+            match key.trim() {
+                "m" => params.m_cost = value,
+                "t" => params.t_cost = value,
+                "p" => params.p_cost = value,
+                _ => return Err(invalid()),
+            }
+        }
+
+        params.validate()?;
+
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_argon2_default() {
+        let pwd = "不立文字".as_bytes();
+        let salt = b"some salt of 16b";
+
+        let mut expected = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(pwd, salt, &mut expected)
+            .unwrap();
+
+        let mut actual = [0u8; 32];
+        Argon2Params::default()
+            .hash_password_into(pwd, salt, &mut actual)
+            .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let params = Argon2Params::default();
+        assert_eq!(params.to_string(), "argon2id,m=19456,t=2,p=1");
+        assert_eq!(params.to_string().parse::<Argon2Params>().unwrap(), params);
+
+        let params = "argon2i,m=65536,t=3".parse::<Argon2Params>().unwrap();
+        assert_eq!(params.algorithm, Argon2Algorithm::Argon2i);
+        assert_eq!(params.m_cost, 65536);
+        assert_eq!(params.t_cost, 3);
+        assert_eq!(params.p_cost, 1);
+
+        assert!("argon2x,m=1".parse::<Argon2Params>().is_err());
+        assert!("argon2id,m=1".parse::<Argon2Params>().is_err());
+        assert!("argon2id,q=1".parse::<Argon2Params>().is_err());
+
+        assert!(
+            "argon2id,m=1048576,t=64,p=16"
+                .parse::<Argon2Params>()
+                .is_ok()
+        );
+        assert!("argon2id,m=1048577".parse::<Argon2Params>().is_err());
+        assert!("argon2id,t=65".parse::<Argon2Params>().is_err());
+        assert!("argon2id,p=17".parse::<Argon2Params>().is_err());
+    }
+}
@@ -1,12 +1,57 @@
+mod error;
 mod xinyin;
+pub use xinyin::MIN_RANGE_COUNT;
+
+pub use error::{Error, PinyinSyllable, Result, UnknownCharacter, UnknownIdiom};
+
+pub mod kdf;
+pub use kdf::{Argon2Algorithm, Argon2Params};
+
+#[cfg(any(feature = "generate", feature = "import"))]
+pub use xinyin::{
+    card::RecoveryCard,
+    charinfo::{CharInfo, CharsetFilter, char_info},
+    checksum::MAX_CHECKSUM_WORDS,
+    fingerprint::{HeartprintFingerprint, heartprint_fingerprint},
+    heartprint::{heartprint_variants, normalize_heartprint},
+    idioms::{idiom_list, idiom_max_start},
+    pinyin::{pinyin_of, pinyin_to_words, pinyin_to_words_with},
+    primary::{CustomCharset, PrimaryCharset},
+    radix::RADIXES,
+    reed_solomon::MAX_PARITY_WORDS,
+    scheme::{Homophones, Scheme, SchemeVersion},
+    shamir::MAX_SHARES,
+    strength::{HeartprintStrength, StrengthLevel, StrengthWarning, heartprint_strength},
+};
 
 #[cfg(feature = "generate")]
-pub use xinyin::generate::generate_words32;
+pub use xinyin::{
+    entropy::{COIN_FLIPS_FOR_256_BITS, DICE_ROLLS_FOR_256_BITS, UserEntropy, generate_secret_key},
+    generate::{
+        generate_recovery_card, generate_recovery_card_with_rng, generate_words, generate_words32,
+        generate_words32_with,
+    },
+    shamir::split_words,
+};
 
 #[cfg(feature = "import")]
-pub use xinyin::import::import_words32;
+pub use xinyin::{
+    confusables::confusables_of,
+    import::{
+        import_recovery_card, import_words, import_words_repair, import_words32,
+        import_words32_pinyin, import_words32_repair, import_words32_with,
+    },
+    normalize::{NormalizedWords, Replacement, normalize_words, normalize_words_in, standard_form},
+    shamir::combine_words,
+};
 
 #[cfg(feature = "sks")]
 pub mod sks;
 #[cfg(feature = "sks")]
-pub use sks::{EncryptedSk, SksStore};
+pub use sks::{EncryptedSk, SksStore, address_of, verify_address};
+
+#[cfg(all(feature = "import", feature = "sks"))]
+pub use xinyin::import::{import_words32_verified, import_words32_verified_with};
+
+#[cfg(all(feature = "generate", feature = "import", feature = "sks"))]
+pub use xinyin::rekey::{rekey_words, rekey_words32};
@@ -1,7 +1,8 @@
 /// sk - Secret key
 mod utils;
 
-use anyhow::{Context, Result, bail};
+use crate::kdf::Argon2Params;
+use crate::{Error, Result};
 use base64::{Engine, engine::general_purpose::STANDARD_NO_PAD as base64_engine};
 use bs58::encode;
 use ed25519_dalek::{SigningKey, ed25519::signature::SignerMut};
@@ -12,6 +13,8 @@ use std::{
 use utils::{aes_gcm_decrypt, aes_gcm_encrypt};
 // use web_sys::console;
 
+/// 存储格式中 Argon2 参数与密文之间的分隔符，base64 中不会出现
+const PARAMS_SEPARATOR: char = '$';
 const SALT_SIZE: usize = 16;
 const NONCE_SIZE: usize = 12;
 /// secret key size for ed25519
@@ -30,7 +33,9 @@ impl EncryptedSks {
     fn get(address: &str) -> Result<Option<Arc<EncryptedSk>>> {
         match ENCRYPTED_SKS.read() {
             Ok(encrypted_sks) => Ok(encrypted_sks.get(address).cloned()),
-            Err(_) => bail!("failed to read ENCRYPTED_SKS"),
+            Err(_) => Err(Error::StoreFailure(
+                "failed to read ENCRYPTED_SKS".to_string(),
+            )),
         }
     }
 
@@ -40,13 +45,34 @@ impl EncryptedSks {
                 encrypted_sks.insert(address.to_string(), encrypted_sk.clone());
                 Ok(())
             }
-            Err(_) => bail!("failed to write ENCRYPTED_SKS"),
+            Err(_) => Err(Error::StoreFailure(
+                "failed to write ENCRYPTED_SKS".to_string(),
+            )),
         }
     }
 }
 
+/// 私钥对应的钱包地址，即 ed25519 公钥的 base58 编码
+pub fn address_of(sk: &SecretKey) -> String {
+    let signing_key = SigningKey::from_bytes(sk);
+    encode(signing_key.verifying_key().as_bytes()).into_string()
+}
+
+/// 检查私钥是否对应预期的钱包地址，不符时返回 [`Error::AddressMismatch`]
+pub fn verify_address(sk: &SecretKey, expected_address: &str) -> Result<()> {
+    let actual = address_of(sk);
+    if actual != expected_address.trim() {
+        return Err(Error::AddressMismatch {
+            expected: expected_address.trim().to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
 pub trait SksStore {
     fn load_encrypted_sks(&self) -> Vec<String>;
+    /// 保存一条加密的私钥记录，失败时通常返回 [`Error::StoreFailure`]
     fn save_encrypted_sk(&self, encrypted_sk_bs64: &str) -> Result<()>;
 }
 
@@ -56,59 +82,74 @@ pub struct EncryptedSk {
     pub encrypted_sk: Vec<u8>,
     pub salt: Salt,
     pub nonce: Nonce,
+    /// 派生 AES-GCM 密钥的 Argon2 参数，随记录一起保存
+    pub argon2: Argon2Params,
 }
 
 impl EncryptedSk {
     /// Get an encrypted secret key by address
     pub fn get_by_address(address: &str, pwd: &str, store: &impl SksStore) -> Result<Arc<Self>> {
-        if let Some(encrypted_sk) = EncryptedSks::get(address)? {
-            if let Ok(_) = encrypted_sk.decrypt(pwd) {
-                return Ok(encrypted_sk.clone());
-            }
+        if let Some(encrypted_sk) = EncryptedSks::get(address)?
+            && encrypted_sk.decrypt(pwd).is_ok()
+        {
+            return Ok(encrypted_sk.clone());
         }
 
         let address = address.to_string();
         // console::log_1(&format!("get encrypted sk by address: {}", address).into());
         // console::log_1(&format!("pwd: {}", pwd).into());
 
+        // 格式不对或 Argon2 参数超出上限的记录跳过，不影响其它记录
+        let mut invalid = 0;
         for encrypted_sk_base64 in store.load_encrypted_sks() {
-            let encrypted_sk = Self::from_base64(&encrypted_sk_base64, &address)
-                .context("loop stored encrypted sks failed")?;
+            let Ok(encrypted_sk) = Self::from_base64(&encrypted_sk_base64, &address) else {
+                invalid += 1;
+                continue;
+            };
 
-            if let Ok(_) = encrypted_sk.decrypt(pwd) {
-                EncryptedSks::insert(&address, &encrypted_sk)
-                    .context("failed to insert encrypted sk into cache")?;
+            if encrypted_sk.decrypt(pwd).is_ok() {
+                EncryptedSks::insert(&address, &encrypted_sk)?;
 
                 return Ok(encrypted_sk);
             }
         }
 
-        bail!("secret key not found by address: {}", address);
+        Err(Error::NotFound(format!(
+            "secret key not found by address: {} ({} invalid records skipped)",
+            address, invalid
+        )))
     }
 
     pub fn encrypt_sk(sk: &SecretKey, pwd: &str, store: &impl SksStore) -> Result<Arc<Self>> {
-        let signing_key = SigningKey::from_bytes(sk);
-        let address = encode(signing_key.verifying_key().as_bytes()).into_string();
+        Self::encrypt_sk_with(sk, pwd, &Argon2Params::default(), store)
+    }
+
+    /// 用指定的 Argon2 参数加密保存 secret key，参数会记录在保存的记录里
+    pub fn encrypt_sk_with(
+        sk: &SecretKey,
+        pwd: &str,
+        argon2: &Argon2Params,
+        store: &impl SksStore,
+    ) -> Result<Arc<Self>> {
+        let address = address_of(sk);
 
         if let Ok(encrypted_sk) = EncryptedSk::get_by_address(&address, pwd, store) {
             return Ok(encrypted_sk);
         }
 
-        let (encrypted_sk, salt, nonce) = aes_gcm_encrypt(pwd, &address, sk)?;
+        let (encrypted_sk, salt, nonce) = aes_gcm_encrypt(pwd, &address, sk, argon2)?;
 
         let encrypted_sk = Arc::new(EncryptedSk {
             address: address.clone(),
             encrypted_sk,
             salt,
             nonce,
+            argon2: *argon2,
         });
 
-        store
-            .save_encrypted_sk(&encrypted_sk.to_base64())
-            .context("failed to save encrypted sk base64 to store")?;
+        store.save_encrypted_sk(&encrypted_sk.to_base64())?;
 
-        EncryptedSks::insert(&address, &encrypted_sk)
-            .context("failed to insert encrypted sk into cache")?;
+        EncryptedSks::insert(&address, &encrypted_sk)?;
 
         Ok(encrypted_sk)
     }
@@ -116,13 +157,13 @@ impl EncryptedSk {
 
 impl EncryptedSk {
     pub fn sign_message(&self, message: &[u8], pwd: &str) -> Result<Vec<u8>> {
-        let sk = self.decrypt(pwd).context("failed to sign message")?;
+        let sk = self.decrypt(pwd)?;
 
         let mut signing_key = SigningKey::from_bytes(&sk);
 
         let signature = signing_key
             .try_sign(message)
-            .context("failed to sign message")?;
+            .map_err(|err| Error::SignFailure(format!("ed25519: {}", err)))?;
 
         Ok(signature.to_bytes().to_vec())
     }
@@ -135,38 +176,52 @@ impl EncryptedSk {
             &self.address,
             &self.nonce,
             &self.encrypted_sk,
+            &self.argon2,
         )
-        .context("failed to decrypt encrypted sk")
     }
 
+    /// 默认参数的记录只有 base64，与旧版本兼容；
+    /// 其他参数的记录为 `argon2id,m=65536,t=3,p=1$<base64>`
     fn to_base64(&self) -> String {
         let mut encrypted_sk_bin = Vec::new();
         encrypted_sk_bin.extend_from_slice(&self.salt);
         encrypted_sk_bin.extend_from_slice(&self.nonce);
         encrypted_sk_bin.extend_from_slice(&self.encrypted_sk);
 
-        base64_engine.encode(&encrypted_sk_bin)
+        let encrypted_sk_base64 = base64_engine.encode(&encrypted_sk_bin);
+        if self.argon2.is_default() {
+            encrypted_sk_base64
+        } else {
+            format!("{}{}{}", self.argon2, PARAMS_SEPARATOR, encrypted_sk_base64)
+        }
     }
 
     fn from_base64(encrypted_sk_base64: &str, address: &str) -> Result<Arc<Self>> {
-        let encrypted_sk_bin = base64_engine.decode(encrypted_sk_base64).context(format!(
-            "from_base64: failed to decode encrypted sk base64: {}",
-            encrypted_sk_base64
-        ))?;
+        let (argon2, encrypted_sk_base64) = match encrypted_sk_base64.split_once(PARAMS_SEPARATOR) {
+            Some((params, encrypted_sk_base64)) => {
+                (params.parse::<Argon2Params>()?, encrypted_sk_base64)
+            }
+            None => (Argon2Params::default(), encrypted_sk_base64),
+        };
+
+        let encrypted_sk_bin = base64_engine.decode(encrypted_sk_base64).map_err(|_| {
+            Error::InvalidFormat(format!(
+                "from_base64: failed to decode encrypted sk base64: {}",
+                encrypted_sk_base64
+            ))
+        })?;
 
         if encrypted_sk_bin.len() < (SALT_SIZE + NONCE_SIZE + SK_SIZE) {
-            bail!(
+            return Err(Error::InvalidFormat(format!(
                 "from_base64: invalid encrypted sk bin length: {}",
                 encrypted_sk_bin.len()
-            );
+            )));
         }
 
-        let salt: Salt = encrypted_sk_bin[0..SALT_SIZE]
-            .try_into()
-            .context("from_base64: failed to parse salt")?;
-        let nonce: Nonce = encrypted_sk_bin[SALT_SIZE..(SALT_SIZE + NONCE_SIZE)]
-            .try_into()
-            .context("from_base64: failed to parse nonce")?;
+        let mut salt: Salt = [0; SALT_SIZE];
+        salt.copy_from_slice(&encrypted_sk_bin[0..SALT_SIZE]);
+        let mut nonce: Nonce = [0; NONCE_SIZE];
+        nonce.copy_from_slice(&encrypted_sk_bin[SALT_SIZE..(SALT_SIZE + NONCE_SIZE)]);
         let encrypted_sk: Vec<u8> = encrypted_sk_bin[SALT_SIZE + NONCE_SIZE..].to_vec();
 
         Ok(Arc::new(Self {
@@ -174,6 +229,103 @@ impl EncryptedSk {
             nonce,
             encrypted_sk,
             address: address.into(),
+            argon2,
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypted_sk(argon2: Argon2Params) -> EncryptedSk {
+        EncryptedSk {
+            address: "7tWEmKfxBwm517CQtbEVNNMGRQeZSN2gwuZWzmxkumTc".to_string(),
+            encrypted_sk: vec![1; SK_SIZE + 16],
+            salt: [2; SALT_SIZE],
+            nonce: [3; NONCE_SIZE],
+            argon2,
+        }
+    }
+
+    #[test]
+    fn test_verify_address() {
+        let sk = [
+            194, 124, 163, 194, 143, 43, 107, 102, 250, 172, 57, 40, 79, 56, 100, 215, 145, 185,
+            195, 196, 3, 98, 199, 30, 111, 112, 46, 51, 223, 108, 157, 199,
+        ];
+        let address = "2gWUYMLPqVpqXnRdrF8h1s1p3y3C2xtrxQjyEzHgbEBG";
+        assert_eq!(address_of(&sk), address);
+        assert!(verify_address(&sk, address).is_ok());
+
+        let err = verify_address(&[0u8; SK_SIZE], address).unwrap_err();
+        let Error::AddressMismatch { expected, .. } = err else {
+            panic!("expected address mismatch, got {:?}", err);
+        };
+        assert_eq!(expected, address);
+    }
+
+    #[test]
+    fn test_base64_default_params_is_legacy_format() {
+        let encrypted_sk = encrypted_sk(Argon2Params::default());
+        let encrypted_sk_base64 = encrypted_sk.to_base64();
+        assert!(!encrypted_sk_base64.contains(PARAMS_SEPARATOR));
+
+        let decoded = EncryptedSk::from_base64(&encrypted_sk_base64, &encrypted_sk.address);
+        assert_eq!(*decoded.unwrap(), encrypted_sk);
+    }
+
+    struct MemoryStore(Vec<String>);
+
+    impl SksStore for MemoryStore {
+        fn load_encrypted_sks(&self) -> Vec<String> {
+            self.0.clone()
+        }
+
+        fn save_encrypted_sk(&self, _: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_get_by_address_skips_invalid_records() {
+        let sk = [5u8; SK_SIZE];
+        let argon2 = "argon2id,m=64,t=1,p=1".parse::<Argon2Params>().unwrap();
+        let address = address_of(&sk);
+        let (encrypted, salt, nonce) = aes_gcm_encrypt("pwd", &address, &sk, &argon2).unwrap();
+        let record = EncryptedSk {
+            address: address.clone(),
+            encrypted_sk: encrypted,
+            salt,
+            nonce,
+            argon2,
+        }
+        .to_base64();
+
+        // 前面的记录格式不对或参数过大，都不影响找到后面的记录
+        let store = MemoryStore(vec![
+            "not base64!".to_string(),
+            format!(
+                "argon2id,m=4294967295,t=1,p=1{}{}",
+                PARAMS_SEPARATOR, "AAAA"
+            ),
+            record,
+        ]);
+        let found = EncryptedSk::get_by_address(&address, "pwd", &store).unwrap();
+        assert_eq!(found.address, address);
+
+        let err = EncryptedSk::get_by_address("unknown", "pwd", &store).unwrap_err();
+        assert_eq!(err.code(), "not_found");
+        assert!(err.to_string().contains("2 invalid records skipped"));
+    }
+
+    #[test]
+    fn test_base64_records_argon2_params() {
+        let encrypted_sk = encrypted_sk("argon2id,m=65536,t=3,p=1".parse().unwrap());
+        let encrypted_sk_base64 = encrypted_sk.to_base64();
+        assert!(encrypted_sk_base64.starts_with("argon2id,m=65536,t=3,p=1$"));
+
+        let decoded = EncryptedSk::from_base64(&encrypted_sk_base64, &encrypted_sk.address);
+        assert_eq!(*decoded.unwrap(), encrypted_sk);
+    }
+}
@@ -1,9 +1,9 @@
+use crate::kdf::Argon2Params;
+use crate::{Error, Result};
 use aes_gcm::{
     AeadCore, Aes256Gcm, KeyInit,
     aead::{Aead, Payload},
 };
-use anyhow::{Context, Result};
-use argon2::Argon2;
 use rand::{TryRngCore, rngs::OsRng};
 
 pub(super) fn aes_gcm_decrypt(
@@ -12,8 +12,9 @@ pub(super) fn aes_gcm_decrypt(
     vk_bs58: &str,
     nonce: &[u8; 12],
     ciphertext: &[u8],
+    argon2: &Argon2Params,
 ) -> Result<[u8; 32]> {
-    let aes_key = generate_aes_key(pwd, salt).context("failed to decrypt(aes-gcm) data")?;
+    let aes_key = generate_aes_key(pwd, salt, argon2)?;
 
     let cipher = Aes256Gcm::new(&aes_key.into());
 
@@ -25,11 +26,13 @@ pub(super) fn aes_gcm_decrypt(
                 msg: ciphertext,
             },
         )
-        .context("failed to decrypt(aes-gcm) data")?;
+        .map_err(|_| {
+            Error::DecryptFailure("aes-gcm: wrong password or corrupted data".to_string())
+        })?;
 
     let sk: [u8; 32] = plaintext
         .try_into()
-        .map_err(|_| anyhow::anyhow!("decrypted(aes-gcm) data is not 32 bytes"))?;
+        .map_err(|_| Error::WrongLength("decrypted(aes-gcm) data is not 32 bytes".to_string()))?;
 
     Ok(sk)
 }
@@ -38,22 +41,24 @@ pub(super) fn aes_gcm_encrypt(
     pwd: &str,
     vk_bs58: &str,
     sk: &[u8; 32],
+    argon2: &Argon2Params,
 ) -> Result<(Vec<u8>, [u8; 16], [u8; 12])> {
     let salt = generate_salt_from_rng()?;
-    let aes_key = generate_aes_key(pwd, salt.as_ref())?;
+    let aes_key = generate_aes_key(pwd, salt.as_ref(), argon2)?;
 
     let cipher = Aes256Gcm::new(&aes_key.into());
-    let nonce = Aes256Gcm::generate_nonce().context("failed to encrypt(aes-gcm) data")?;
+    let nonce = Aes256Gcm::generate_nonce()
+        .map_err(|err| Error::RandomFailure(format!("aes-gcm nonce: {}", err)))?;
 
     let ciphertext = cipher
         .encrypt(
-            &nonce.into(),
+            &nonce,
             Payload {
                 aad: vk_bs58.as_bytes(),
                 msg: sk,
             },
         )
-        .context("failed to encrypt(aes-gcm) data")?;
+        .map_err(|err| Error::EncryptFailure(format!("aes-gcm: {}", err)))?;
 
     Ok((ciphertext, salt, nonce.into()))
 }
@@ -63,16 +68,14 @@ fn generate_salt_from_rng() -> Result<[u8; 16]> {
     let mut salt = [0u8; 16];
     OsRng
         .try_fill_bytes(&mut salt)
-        .context("failed to generate salt when osrng fill buffer")?;
+        .map_err(|err| Error::RandomFailure(format!("osrng: {}", err)))?;
     Ok(salt)
 }
 
 #[inline(always)]
-fn generate_aes_key(pwd: &str, salt: &[u8]) -> Result<[u8; 32]> {
+fn generate_aes_key(pwd: &str, salt: &[u8], argon2: &Argon2Params) -> Result<[u8; 32]> {
     let mut aes_key = [0u8; 32];
-    Argon2::default()
-        .hash_password_into(pwd.as_bytes(), salt, &mut aes_key)
-        .context("failed to generate aes key by argon2")?;
+    argon2.hash_password_into(pwd.as_bytes(), salt, &mut aes_key)?;
     Ok(aes_key)
 }
 
@@ -89,11 +92,13 @@ mod tests {
             195, 196, 3, 98, 199, 30, 111, 112, 46, 51, 223, 108, 157, 199,
         ];
 
+        let argon2 = Argon2Params::default();
+
         let (encrypt_key, salt, nonce) =
-            aes_gcm_encrypt(pwd, vk_bs58, &sk).expect("Failed to encrypt with AES-GCM");
+            aes_gcm_encrypt(pwd, vk_bs58, &sk, &argon2).expect("Failed to encrypt with AES-GCM");
         println!("AES-GCM encrypt result: {:?}", encrypt_key);
 
-        let decrypted = aes_gcm_decrypt(pwd, &salt, vk_bs58, &nonce, &encrypt_key)
+        let decrypted = aes_gcm_decrypt(pwd, &salt, vk_bs58, &nonce, &encrypt_key, &argon2)
             .expect("Failed to decrypt with AES-GCM");
 
         assert_eq!(decrypted, sk);
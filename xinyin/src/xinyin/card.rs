@@ -0,0 +1,658 @@
+use crate::{Error, Result};
+use std::{fmt, str::FromStr};
+
+use super::primary::PrimaryCharset;
+use super::scheme::{Homophones, Scheme, SchemeVersion};
+use crate::kdf::{Argon2Algorithm, Argon2Params};
+
+/// 文本形式的前缀
+const CARD_PREFIX: &str = "XINYIN";
+/// 二进制形式的魔数
+const CARD_MAGIC: &[u8; 2] = b"XY";
+/// 二进制格式版本，与方案版本无关
+const CARD_FORMAT: u8 = 1;
+/// 选项标志：去掉形近字
+const FLAG_CONFUSABLE_FREE: u8 = 1;
+/// 选项标志：去掉同音字
+const FLAG_HOMOPHONE_FREE: u8 = 1 << 1;
+/// 选项标志：去掉同音字时不论声调
+const FLAG_IGNORE_TONES: u8 = 1 << 2;
+/// 选项标志：成语编码
+const FLAG_IDIOMS: u8 = 1 << 3;
+/// 选项标志：规范化心印时去掉标点
+const FLAG_STRIP_PUNCTUATION: u8 = 1 << 4;
+const KNOWN_FLAGS: u8 = FLAG_CONFUSABLE_FREE
+    | FLAG_HOMOPHONE_FREE
+    | FLAG_IGNORE_TONES
+    | FLAG_IDIOMS
+    | FLAG_STRIP_PUNCTUATION;
+
+/// 恢复卡：除心印和密钥之外还原所需的全部信息
+///
+/// 包括方案版本、基础字集及范围、选字条件、是否去掉形近字和同音字、心印字表的字数、是否用成语编码、心印是否去掉标点、Argon2 参数、钱包标签、随机盐、校验字和纠错字个数，以及助记字本身，
+/// 还原时只需要恢复卡和心印。
+///
+/// 文本形式为一行，默认值的字段省略：
+///
+/// ```text
+/// XINYIN;v=2;range=6,666;charset=big5;filter=level<=1;exclude=confusables;radix=1024;encoding=idioms;heartprint=strip-punctuation;kdf=argon2id,m=65536,t=3,p=1;checksum=1;parity=4;salt=9f…;label=0;words=㊁问抖…
+/// ```
+///
+/// 二进制形式（[`RecoveryCard::to_bytes`]）更紧凑，适合生成二维码。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryCard {
+    pub scheme: Scheme,
+    /// 心印助记字，含版本标记、校验字和纠错字
+    pub words: String,
+}
+
+impl RecoveryCard {
+    pub fn new(scheme: Scheme, words: String) -> Self {
+        Self { scheme, words }
+    }
+
+    /// 二进制形式
+    ///
+    /// `XY` | 格式版本 | 方案版本 | start(u16) | count(u16) | 算法 | m(u32) | t(u32) | p(u32)
+    /// | 校验字个数 | 纠错字个数 | 盐长度 | 盐 | 标签长度 | 标签 | 字集长度 | 字集（文本形式）
+    /// | 选字条件长度 | 选字条件（文本形式，没有时长度为 0）
+    /// | 选项标志（位 0：去掉形近字，位 1：去掉同音字，位 2：不论声调，位 3：成语编码，位 4：心印去掉标点）| 心印字表字数(u16) | 助记字(UTF-8)，
+    /// 整数均为大端，长度均为 u8。
+    ///
+    /// 有字段超出二进制形式能表示的范围时（如标签超过 255 字节）返回 [`Error::InvalidParams`]。
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let scheme = &self.scheme;
+        let mut bytes = Vec::new();
+        bytes.extend(CARD_MAGIC);
+        bytes.push(CARD_FORMAT);
+        bytes.push(scheme.version.number());
+        bytes.extend(to_u16("start", scheme.start)?.to_be_bytes());
+        bytes.extend(to_u16("count", scheme.count)?.to_be_bytes());
+        bytes.push(algorithm_to_byte(scheme.argon2.algorithm));
+        bytes.extend(scheme.argon2.m_cost.to_be_bytes());
+        bytes.extend(scheme.argon2.t_cost.to_be_bytes());
+        bytes.extend(scheme.argon2.p_cost.to_be_bytes());
+        bytes.push(to_u8("checksum", scheme.checksum)?);
+        bytes.push(to_u8("parity", scheme.parity)?);
+        push_field(&mut bytes, "salt", &scheme.salt)?;
+        push_field(&mut bytes, "label", scheme.label.as_bytes())?;
+        push_field(&mut bytes, "charset", scheme.primary.to_string().as_bytes())?;
+        let filter = scheme
+            .filter
+            .as_ref()
+            .map(|filter| filter.to_string())
+            .unwrap_or_default();
+        push_field(&mut bytes, "filter", filter.as_bytes())?;
+        let mut flags = 0;
+        if scheme.confusable_free {
+            flags |= FLAG_CONFUSABLE_FREE;
+        }
+        match scheme.homophones {
+            Homophones::Allowed => {}
+            Homophones::DistinctTones => flags |= FLAG_HOMOPHONE_FREE,
+            Homophones::DistinctSyllables => flags |= FLAG_HOMOPHONE_FREE | FLAG_IGNORE_TONES,
+        }
+        if scheme.idioms {
+            flags |= FLAG_IDIOMS;
+        }
+        if scheme.strip_punctuation {
+            flags |= FLAG_STRIP_PUNCTUATION;
+        }
+        bytes.push(flags);
+        bytes.extend(to_u16("radix", scheme.radix)?.to_be_bytes());
+        bytes.extend(self.words.as_bytes());
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader(bytes);
+        if reader.take(2)? != CARD_MAGIC {
+            return Err(invalid("not a xinyin recovery card".to_string()));
+        }
+        let format = reader.u8()?;
+        if format != CARD_FORMAT {
+            return Err(invalid(format!(
+                "unsupported recovery card format({})",
+                format
+            )));
+        }
+
+        let version = reader.u8()?;
+        let version = SchemeVersion::from_number(version)
+            .ok_or_else(|| Error::UnsupportedVersion(format!("v{}", version)))?;
+        let mut scheme = Scheme::new(version, reader.u16()? as usize, reader.u16()? as usize);
+        scheme.argon2 = Argon2Params {
+            algorithm: algorithm_from_byte(reader.u8()?)?,
+            m_cost: reader.u32()?,
+            t_cost: reader.u32()?,
+            p_cost: reader.u32()?,
+        };
+        scheme.argon2.validate()?;
+        scheme.checksum = reader.u8()? as usize;
+        scheme.parity = reader.u8()? as usize;
+        scheme.check_lengths(None)?;
+        scheme.salt = reader.field()?.to_vec();
+        scheme.label = String::from_utf8(reader.field()?.to_vec())
+            .map_err(|_| invalid("recovery card label is not valid utf-8".to_string()))?;
+        scheme.primary = std::str::from_utf8(reader.field()?)
+            .map_err(|_| invalid("recovery card charset is not valid utf-8".to_string()))?
+            .parse()?;
+        let filter = std::str::from_utf8(reader.field()?)
+            .map_err(|_| invalid("recovery card filter is not valid utf-8".to_string()))?;
+        if !filter.is_empty() {
+            scheme.filter = Some(filter.parse()?);
+        }
+        let flags = reader.u8()?;
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(invalid(format!("unknown recovery card flags({})", flags)));
+        }
+        scheme.confusable_free = flags & FLAG_CONFUSABLE_FREE != 0;
+        scheme.homophones = match (
+            flags & FLAG_HOMOPHONE_FREE != 0,
+            flags & FLAG_IGNORE_TONES != 0,
+        ) {
+            (false, _) => Homophones::Allowed,
+            (true, false) => Homophones::DistinctTones,
+            (true, true) => Homophones::DistinctSyllables,
+        };
+        scheme.idioms = flags & FLAG_IDIOMS != 0;
+        scheme.strip_punctuation = flags & FLAG_STRIP_PUNCTUATION != 0;
+        scheme.radix = reader.u16()? as usize;
+        let words = String::from_utf8(reader.0.to_vec())
+            .map_err(|_| invalid("recovery card words is not valid utf-8".to_string()))?;
+
+        Self::checked(scheme, words)
+    }
+
+    fn checked(scheme: Scheme, words: String) -> Result<Self> {
+        let (version, _) = SchemeVersion::detect(&words)?;
+        if version != scheme.version {
+            return Err(invalid(format!(
+                "recovery card says scheme v{}, but words are v{}",
+                scheme.version.number(),
+                version.number()
+            )));
+        }
+        Ok(Self { scheme, words })
+    }
+}
+
+impl fmt::Display for RecoveryCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = &self.scheme;
+        write!(
+            f,
+            "{};v={};range={},{}",
+            CARD_PREFIX,
+            scheme.version.number(),
+            scheme.start,
+            scheme.count
+        )?;
+        if scheme.primary != PrimaryCharset::default() {
+            write!(f, ";charset={}", scheme.primary)?;
+        }
+        if let Some(filter) = &scheme.filter {
+            write!(f, ";filter={}", filter)?;
+        }
+        let mut excluded = Vec::new();
+        if scheme.confusable_free {
+            excluded.push("confusables");
+        }
+        match scheme.homophones {
+            Homophones::Allowed => {}
+            Homophones::DistinctTones => excluded.push("homophones"),
+            Homophones::DistinctSyllables => excluded.push("homophones-toneless"),
+        }
+        if !excluded.is_empty() {
+            write!(f, ";exclude={}", excluded.join(","))?;
+        }
+        if scheme.radix != 256 {
+            write!(f, ";radix={}", scheme.radix)?;
+        }
+        if scheme.idioms {
+            write!(f, ";encoding=idioms")?;
+        }
+        if scheme.strip_punctuation {
+            write!(f, ";heartprint=strip-punctuation")?;
+        }
+        if !scheme.argon2.is_default() {
+            write!(f, ";kdf={}", scheme.argon2)?;
+        }
+        if scheme.checksum > 0 {
+            write!(f, ";checksum={}", scheme.checksum)?;
+        }
+        if scheme.parity > 0 {
+            write!(f, ";parity={}", scheme.parity)?;
+        }
+        if !scheme.salt.is_empty() {
+            let salt = scheme
+                .salt
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            write!(f, ";salt={}", salt)?;
+        }
+        if !scheme.label.is_empty() {
+            write!(f, ";label={}", escape(&scheme.label))?;
+        }
+        write!(f, ";words={}", self.words)
+    }
+}
+
+impl FromStr for RecoveryCard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut fields = s.trim().split(';');
+        if fields.next() != Some(CARD_PREFIX) {
+            return Err(invalid("not a xinyin recovery card".to_string()));
+        }
+
+        let mut version = None;
+        let mut range = None;
+        let mut words = None;
+        let mut scheme = Scheme::v1(0, 0);
+        for field in fields {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("invalid recovery card field `{}`", field)))?;
+            match key {
+                "v" => {
+                    let number = value.parse::<u8>().ok();
+                    version = Some(
+                        number
+                            .and_then(SchemeVersion::from_number)
+                            .ok_or_else(|| Error::UnsupportedVersion(format!("v{}", value)))?,
+                    );
+                }
+                "range" => {
+                    range = value.split_once(',').and_then(|(start, count)| {
+                        Some((start.parse().ok()?, count.parse().ok()?))
+                    });
+                    if range.is_none() {
+                        return Err(invalid(format!("invalid recovery card range `{}`", value)));
+                    }
+                }
+                "charset" => scheme.primary = value.parse()?,
+                "filter" => scheme.filter = Some(value.parse()?),
+                "exclude" => {
+                    for excluded in value.split(',') {
+                        match excluded {
+                            "confusables" => scheme.confusable_free = true,
+                            "homophones" => scheme.homophones = Homophones::DistinctTones,
+                            "homophones-toneless" => {
+                                scheme.homophones = Homophones::DistinctSyllables
+                            }
+                            _ => {
+                                return Err(invalid(format!(
+                                    "unknown recovery card exclusion `{}`",
+                                    excluded
+                                )));
+                            }
+                        }
+                    }
+                }
+                "radix" => {
+                    scheme.radix = value
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid recovery card radix `{}`", value)))?
+                }
+                "encoding" => match value {
+                    "idioms" => scheme.idioms = true,
+                    _ => {
+                        return Err(invalid(format!(
+                            "unknown recovery card encoding `{}`",
+                            value
+                        )));
+                    }
+                },
+                "heartprint" => match value {
+                    "strip-punctuation" => scheme.strip_punctuation = true,
+                    _ => {
+                        return Err(invalid(format!(
+                            "unknown recovery card heartprint option `{}`",
+                            value
+                        )));
+                    }
+                },
+                "kdf" => scheme.argon2 = value.parse()?,
+                "checksum" => {
+                    scheme.checksum = value.parse().map_err(|_| {
+                        invalid(format!("invalid recovery card checksum `{}`", value))
+                    })?
+                }
+                "parity" => {
+                    scheme.parity = value
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid recovery card parity `{}`", value)))?
+                }
+                "salt" => scheme.salt = parse_hex(value)?,
+                "label" => scheme.label = unescape(value)?,
+                "words" => words = Some(value.to_string()),
+                _ => return Err(invalid(format!("unknown recovery card field `{}`", key))),
+            }
+        }
+
+        scheme.version =
+            version.ok_or_else(|| invalid("recovery card has no scheme version".to_string()))?;
+        (scheme.start, scheme.count) =
+            range.ok_or_else(|| invalid("recovery card has no charset range".to_string()))?;
+        let words = words.ok_or_else(|| invalid("recovery card has no words".to_string()))?;
+        scheme.check_lengths(None)?;
+
+        Self::checked(scheme, words)
+    }
+}
+
+fn invalid(msg: String) -> Error {
+    Error::InvalidFormat(msg)
+}
+
+/// 二进制形式中的 u8 字段
+fn to_u8(name: &str, value: usize) -> Result<u8> {
+    u8::try_from(value).map_err(|_| {
+        Error::InvalidParams(format!(
+            "recovery card {}({}) must not exceed {}",
+            name,
+            value,
+            u8::MAX
+        ))
+    })
+}
+
+/// 二进制形式中的 u16 字段
+fn to_u16(name: &str, value: usize) -> Result<u16> {
+    u16::try_from(value).map_err(|_| {
+        Error::InvalidParams(format!(
+            "recovery card {}({}) must not exceed {}",
+            name,
+            value,
+            u16::MAX
+        ))
+    })
+}
+
+/// 写入 u8 长度和内容
+fn push_field(bytes: &mut Vec<u8>, name: &str, field: &[u8]) -> Result<()> {
+    let len = u8::try_from(field.len()).map_err(|_| {
+        Error::InvalidParams(format!(
+            "recovery card {} must not exceed {} bytes, but got {}",
+            name,
+            u8::MAX,
+            field.len()
+        ))
+    })?;
+    bytes.push(len);
+    bytes.extend(field);
+    Ok(())
+}
+
+fn algorithm_to_byte(algorithm: Argon2Algorithm) -> u8 {
+    match algorithm {
+        Argon2Algorithm::Argon2d => 0,
+        Argon2Algorithm::Argon2i => 1,
+        Argon2Algorithm::Argon2id => 2,
+    }
+}
+
+fn algorithm_from_byte(byte: u8) -> Result<Argon2Algorithm> {
+    match byte {
+        0 => Ok(Argon2Algorithm::Argon2d),
+        1 => Ok(Argon2Algorithm::Argon2i),
+        2 => Ok(Argon2Algorithm::Argon2id),
+        _ => Err(invalid(format!("unknown argon2 algorithm({})", byte))),
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(invalid(format!("invalid hex `{}`", hex)));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| invalid(format!("invalid hex `{}`", hex)))
+        })
+        .collect()
+}
+
+/// 标签中的 `%` 和 `;` 转义为 `%25`、`%3B`
+fn escape(label: &str) -> String {
+    label.replace('%', "%25").replace(';', "%3B")
+}
+
+fn unescape(label: &str) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = label;
+    while let Some(i) = rest.find('%') {
+        out.push_str(&rest[..i]);
+        match rest.get(i + 1..i + 3) {
+            Some("25") => out.push('%'),
+            Some("3B") => out.push(';'),
+            _ => return Err(invalid(format!("invalid escape in label `{}`", label))),
+        }
+        rest = &rest[i + 3..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(invalid("recovery card is truncated".to_string()));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// u8 长度和内容
+    fn field(&mut self) -> Result<&'a [u8]> {
+        let len = self.u8()? as usize;
+        self.take(len)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card() -> RecoveryCard {
+        let scheme = Scheme {
+            argon2: "argon2i,m=65536,t=3,p=2".parse().unwrap(),
+            label: "家庭;100%".to_string(),
+            salt: vec![0x9f, 0x00, 0x12],
+            checksum: 1,
+            parity: 4,
+            primary: PrimaryCharset::Big5Common,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        RecoveryCard::new(scheme, "㊁问抖廷亿".to_string())
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let card = card();
+        let text = card.to_string();
+        assert_eq!(
+            text,
+            "XINYIN;v=2;range=6,666;charset=big5;kdf=argon2i,m=65536,t=3,p=2;checksum=1;parity=4;salt=9f0012;label=家庭%3B100%25;words=㊁问抖廷亿"
+        );
+        assert_eq!(text.parse::<RecoveryCard>().unwrap(), card);
+
+        let v1 = RecoveryCard::new(Scheme::v1(6, 666), "问抖廷亿".to_string());
+        assert_eq!(v1.to_string(), "XINYIN;v=1;range=6,666;words=问抖廷亿");
+        assert_eq!(v1.to_string().parse::<RecoveryCard>().unwrap(), v1);
+
+        assert!(
+            "XINYIN;v=1;range=6,666;words=㊁问抖廷亿"
+                .parse::<RecoveryCard>()
+                .is_err()
+        );
+        assert!(
+            "XINYIN;v=2;words=㊁问抖廷亿"
+                .parse::<RecoveryCard>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let card = card();
+        let bytes = card.to_bytes().unwrap();
+        assert_eq!(RecoveryCard::from_bytes(&bytes).unwrap(), card);
+        assert!(RecoveryCard::from_bytes(&bytes[..20]).is_err());
+
+        let v1 = RecoveryCard::new(Scheme::v1(6, 666), "问抖廷亿".to_string());
+        let mut bytes = v1.to_bytes().unwrap();
+        assert_eq!(RecoveryCard::from_bytes(&bytes).unwrap(), v1);
+        // 只有一种格式，其它格式版本不能读取
+        bytes[2] = CARD_FORMAT + 1;
+        assert!(RecoveryCard::from_bytes(&bytes).is_err());
+
+        let filtered = RecoveryCard::new(
+            Scheme {
+                filter: Some("level1-simple".parse().unwrap()),
+                confusable_free: true,
+                homophones: Homophones::DistinctSyllables,
+                radix: 64,
+                strip_punctuation: true,
+                ..Scheme::new(SchemeVersion::V3, 1, 8104)
+            },
+            "㊂问抖廷亿".to_string(),
+        );
+        assert_eq!(
+            filtered.to_string(),
+            "XINYIN;v=3;range=1,8104;filter=level<=1,strokes<=12;exclude=confusables,homophones-toneless;radix=64;heartprint=strip-punctuation;words=㊂问抖廷亿"
+        );
+        assert_eq!(
+            filtered.to_string().parse::<RecoveryCard>().unwrap(),
+            filtered
+        );
+        assert_eq!(
+            RecoveryCard::from_bytes(&filtered.to_bytes().unwrap()).unwrap(),
+            filtered
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_out_of_range_checksum_and_parity() {
+        for field in ["checksum=5", "parity=33"] {
+            let text = format!("XINYIN;v=2;range=6,666;{};words=㊁问抖廷亿", field);
+            let err = text.parse::<RecoveryCard>().unwrap_err();
+            assert_eq!(err.code(), "invalid_params");
+        }
+
+        let scheme = card().scheme;
+        for scheme in [
+            Scheme {
+                checksum: 5,
+                ..scheme.clone()
+            },
+            Scheme {
+                parity: 33,
+                ..scheme
+            },
+        ] {
+            let bytes = RecoveryCard::new(scheme, "㊁问抖廷亿".to_string())
+                .to_bytes()
+                .unwrap();
+            let err = RecoveryCard::from_bytes(&bytes).unwrap_err();
+            assert_eq!(err.code(), "invalid_params");
+        }
+    }
+
+    #[test]
+    fn test_to_bytes_rejects_out_of_range_fields() {
+        let with = |scheme: Scheme| RecoveryCard::new(scheme, "㊁问抖廷亿".to_string()).to_bytes();
+        let scheme = card().scheme;
+        assert!(with(scheme.clone()).is_ok());
+
+        let long_label = Scheme {
+            label: "标".repeat(86),
+            ..scheme.clone()
+        };
+        let err = with(long_label).unwrap_err();
+        assert_eq!(err.code(), "invalid_params");
+        assert!(
+            with(Scheme {
+                salt: vec![0; 256],
+                ..scheme.clone()
+            })
+            .is_err()
+        );
+        assert!(
+            with(Scheme {
+                start: 65536,
+                ..scheme.clone()
+            })
+            .is_err()
+        );
+        assert!(
+            with(Scheme {
+                checksum: 256,
+                ..scheme.clone()
+            })
+            .is_err()
+        );
+        assert!(
+            with(Scheme {
+                parity: 300,
+                ..scheme.clone()
+            })
+            .is_err()
+        );
+        assert!(
+            with(Scheme {
+                radix: 65536,
+                ..scheme
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_recovery_card() {
+        use crate::{generate_recovery_card, import_recovery_card, import_words32_with};
+
+        let sk = [11u8; 32];
+        let scheme = Scheme {
+            checksum: 1,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        }
+        .with_random_salt()
+        .unwrap();
+        let card = generate_recovery_card("不立文字", &scheme, Some(&sk)).unwrap();
+        let card = card.to_string().parse::<RecoveryCard>().unwrap();
+        assert_eq!(import_recovery_card(&card, "不立文字").unwrap(), sk);
+
+        // 缺少盐无法还原
+        let without_salt = Scheme {
+            salt: Vec::new(),
+            ..card.scheme.clone()
+        };
+        assert_ne!(
+            import_words32_with(&card.words, "不立文字", &without_salt).ok(),
+            Some(sk)
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! 通用规范汉字表的字信息，以及按字信息从基础字集中选字
+//!
+//! 连续的范围会把常用的一级字和很多字体、输入法都打不出的三级字混在一起；
+//! 按条件选字可以只用一级字、笔画少的字或最常用的字，让助记字更容易抄写和输入。
+
+use crate::{Error, Result};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use super::MIN_RANGE_COUNT;
+use super::pinyin::pinyin_of;
+
+const META: &str = include_str!("../../8105-meta.txt");
+
+/// 一个字的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharInfo {
+    pub ch: char,
+    /// 字表级别：1 为一级字（常用字，3500 字），2 为二级字，3 为三级字
+    pub level: u8,
+    /// 笔画数
+    pub strokes: u8,
+    /// 字频序号，1 为最常用
+    pub frequency_rank: usize,
+    /// 带调拼音，最常用的读音在前，见 [`pinyin_of`]
+    pub pinyin: Vec<&'static str>,
+    /// 含这个字的常用词，如 `潮` 为 `潮流`，口述时用来区分同音字；少数生僻字没有
+    pub word: Option<&'static str>,
+}
+
+impl CharInfo {
+    /// 口述时的说法，如 `潮流的潮`；没有常用词时为带调拼音，如 `xin1`
+    pub fn spoken(&self) -> String {
+        match (self.word, self.pinyin.first()) {
+            (Some(word), _) => format!("{}的{}", word, self.ch),
+            (None, Some(pinyin)) => pinyin.to_string(),
+            (None, None) => self.ch.to_string(),
+        }
+    }
+}
+
+/// 字信息表的每一行：字、级别、笔画数、字频序号、常用词
+fn entries() -> impl Iterator<Item = (char, u8, u8, usize, Option<&'static str>)> {
+    META.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            Some((
+                fields.next()?.chars().next()?,
+                fields.next()?.parse().ok()?,
+                fields.next()?.parse().ok()?,
+                fields.next()?.parse().ok()?,
+                fields.next(),
+            ))
+        })
+}
+
+/// `ch` 的字信息，不在通用规范汉字表中时返回 `None`
+pub fn char_info(ch: char) -> Option<CharInfo> {
+    entries()
+        .find(|&(c, ..)| c == ch)
+        .map(|(ch, level, strokes, frequency_rank, word)| CharInfo {
+            ch,
+            level,
+            strokes,
+            frequency_rank,
+            pinyin: pinyin_of(ch),
+            word,
+        })
+}
+
+/// 选字条件，只能用于通用规范汉字表，各条件同时满足
+///
+/// 文本形式为逗号分隔的条件，如 `level<=1,strokes<=12`，也可以用 [`CharsetFilter::PRESETS`] 中的名称。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CharsetFilter {
+    /// 最高的字表级别，如 1 表示只用一级字
+    pub max_level: Option<u8>,
+    /// 最多的笔画数
+    pub max_strokes: Option<u8>,
+    /// 最大的字频序号，如 3000 表示只用最常用的 3000 个字
+    pub max_rank: Option<usize>,
+}
+
+impl CharsetFilter {
+    /// 预设的选字条件：名称、说明和条件
+    pub const PRESETS: &'static [(&'static str, &'static str, CharsetFilter)] = &[
+        (
+            "level1",
+            "一级字（3500 字）",
+            CharsetFilter {
+                max_level: Some(1),
+                max_strokes: None,
+                max_rank: None,
+            },
+        ),
+        (
+            "level1-simple",
+            "不超过 12 画的一级字（2824 字）",
+            CharsetFilter {
+                max_level: Some(1),
+                max_strokes: Some(12),
+                max_rank: None,
+            },
+        ),
+        (
+            "top3000",
+            "最常用的 3000 字",
+            CharsetFilter {
+                max_level: None,
+                max_strokes: None,
+                max_rank: Some(3000),
+            },
+        ),
+    ];
+
+    fn matches(&self, level: u8, strokes: u8, rank: usize) -> bool {
+        self.max_level.is_none_or(|max| level <= max)
+            && self.max_strokes.is_none_or(|max| strokes <= max)
+            && self.max_rank.is_none_or(|max| rank <= max)
+    }
+
+    /// 按原来的顺序保留 `words` 中满足条件的字，剩下的字不超过 [`MIN_RANGE_COUNT`](crate::MIN_RANGE_COUNT) 个时返回错误
+    pub fn apply(&self, words: &[char]) -> Result<Vec<char>> {
+        let meta = entries()
+            .map(|(ch, level, strokes, rank, _)| (ch, (level, strokes, rank)))
+            .collect::<HashMap<char, (u8, u8, usize)>>();
+        let selected = words
+            .iter()
+            .copied()
+            .filter(|ch| {
+                meta.get(ch)
+                    .is_some_and(|&(level, strokes, rank)| self.matches(level, strokes, rank))
+            })
+            .collect::<Vec<char>>();
+        if selected.len() <= MIN_RANGE_COUNT {
+            return Err(Error::InvalidParams(format!(
+                "only {} characters match `{}`, more than {} required",
+                selected.len(),
+                self,
+                MIN_RANGE_COUNT
+            )));
+        }
+        Ok(selected)
+    }
+}
+
+impl fmt::Display for CharsetFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(max) = self.max_level {
+            conditions.push(format!("level<={}", max));
+        }
+        if let Some(max) = self.max_strokes {
+            conditions.push(format!("strokes<={}", max));
+        }
+        if let Some(max) = self.max_rank {
+            conditions.push(format!("rank<={}", max));
+        }
+        write!(f, "{}", conditions.join(","))
+    }
+}
+
+impl FromStr for CharsetFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((_, _, filter)) = Self::PRESETS.iter().find(|(name, ..)| *name == s) {
+            return Ok(filter.clone());
+        }
+
+        let invalid = || Error::InvalidFormat(format!("invalid charset filter `{}`", s));
+        let mut filter = CharsetFilter::default();
+        for condition in s.split(',') {
+            let (key, max) = condition.trim().split_once("<=").ok_or_else(invalid)?;
+            let max = max.trim();
+            match key.trim() {
+                "level" => filter.max_level = Some(max.parse().map_err(|_| invalid())?),
+                "strokes" => filter.max_strokes = Some(max.parse().map_err(|_| invalid())?),
+                "rank" => filter.max_rank = Some(max.parse().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_info() {
+        let words = include_str!("../../8105.bin")
+            .chars()
+            .collect::<Vec<char>>();
+        let meta = entries().collect::<Vec<_>>();
+        assert_eq!(meta.len(), words.len());
+        for (i, &(ch, level, strokes, _, word)) in meta.iter().enumerate() {
+            assert_eq!(ch, words[i]);
+            assert!(word.is_none_or(|word| word.contains(ch)), "`{}`", ch);
+            assert_eq!(level, 1 + (i >= 3500) as u8 + (i >= 6500) as u8);
+            // 同级字按笔画数排列
+            if i > 0 && meta[i - 1].1 == level {
+                assert!(meta[i - 1].2 <= strokes, "`{}` has fewer strokes", ch);
+            }
+        }
+        let mut ranks = meta.iter().map(|m| m.3).collect::<Vec<usize>>();
+        ranks.sort();
+        assert!(ranks.iter().enumerate().all(|(i, &rank)| rank == i + 1));
+
+        let info = char_info('国').unwrap();
+        assert_eq!((info.level, info.strokes), (1, 8));
+        assert_eq!(info.pinyin, vec!["guo2"]);
+        assert_eq!(char_info('球').unwrap().strokes, 11);
+        assert_eq!(char_info('潮').unwrap().spoken(), "潮流的潮");
+        assert_eq!(char_info('嘿').unwrap().spoken(), "hei1");
+        assert_eq!(char_info('的').unwrap().frequency_rank, 1);
+        assert!(char_info('國').is_none());
+    }
+
+    #[test]
+    fn test_charset_filter() {
+        let filter = "level1-simple".parse::<CharsetFilter>().unwrap();
+        assert_eq!(filter.to_string(), "level<=1,strokes<=12");
+        assert_eq!(filter.to_string().parse::<CharsetFilter>().unwrap(), filter);
+        assert!("level<1".parse::<CharsetFilter>().is_err());
+        assert!("color<=1".parse::<CharsetFilter>().is_err());
+
+        let words = include_str!("../../8105.bin")
+            .chars()
+            .collect::<Vec<char>>();
+        for (_, _, filter) in CharsetFilter::PRESETS {
+            assert!(filter.apply(&words).is_ok(), "{}", filter);
+        }
+        assert_eq!(filter.apply(&words).unwrap().len(), 2824);
+        assert!(filter.apply(&words[3000..4000]).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_recovery_card_filter() {
+        use crate::{
+            PrimaryCharset, Scheme, SchemeVersion, generate_recovery_card, generate_words32_with,
+            import_recovery_card,
+        };
+
+        let sk = [29u8; 32];
+        let filter = "level1-simple".parse::<CharsetFilter>().unwrap();
+        let scheme = Scheme {
+            filter: Some(filter.clone()),
+            ..Scheme::new(SchemeVersion::V2, 1, 8104)
+        };
+        let card = generate_recovery_card("不立文字", &scheme, Some(&sk)).unwrap();
+        assert!(card.words.chars().skip(1).all(|ch| {
+            let info = char_info(ch).unwrap();
+            info.level == 1 && info.strokes <= 12
+        }));
+        let parsed = card.to_string().parse().unwrap();
+        assert_eq!(
+            import_recovery_card(&parsed, "不立文字").unwrap(),
+            sk.to_vec()
+        );
+
+        // 选字条件只能用于默认字集和 v2
+        for scheme in [
+            Scheme {
+                primary: PrimaryCharset::Big5Common,
+                ..scheme.clone()
+            },
+            Scheme {
+                filter: Some(filter),
+                ..Scheme::v1(1, 8104)
+            },
+        ] {
+            assert!(generate_words32_with("不立文字", &scheme, Some(&sk)).is_err());
+        }
+    }
+}
@@ -1,72 +1,98 @@
-use std::usize;
-
-use anyhow::{Result, bail};
+use crate::{Error, Result};
 use rand::{SeedableRng, seq::IndexedRandom};
 use rand_chacha::ChaCha20Rng;
 use sha2::{Digest, Sha256};
 
-const WORDS: &str = include_str!("../../8105.bin");
-
-/// * start: 1-based index
-fn read_8105_slice(start: usize, len: usize) -> Result<Vec<char>> {
-    if start < 1 || start > 7605 {
-        bail!("start({}) must be between 1 and 7605", start);
-    }
-
-    if len < 500 {
-        bail!("count({}) must be greater than 500", len);
-    }
+use super::confusables::groups;
+use super::pinyin::{pinyin_table, split_tone};
+use super::scheme::Homophones;
 
-    let words = WORDS
-        .chars()
-        .skip(start - 1)
-        .take(len)
-        .collect::<Vec<char>>();
-    if words.len() != len {
-        bail!("expected {} characters, but got {}", len, words.len());
-    }
-    Ok(words)
+/// 以 SHA256(心印) 为种子从基础字集中选出 256 个字
+pub(super) fn generate_256_words(text_in_heart: &str, words: &[char]) -> Result<Vec<char>> {
+    let sha_seed = Sha256::digest(text_in_heart.as_bytes());
+    generate_charset_from_seed(sha_seed.into(), words, 256)
 }
 
-pub(super) fn generate_256_words(
-    text_in_heart: &str,
-    start: usize,
-    count: usize,
-) -> Result<Vec<char>> {
-    if start < 1 || start > 7600 {
-        bail!("start({}) must be between 1 and 7600", start);
-    }
+/// 用指定的种子从基础字集中选出 `size` 个字，默认 256 个，见 `Scheme::radix`
+///
+/// `words` 为按范围和选字条件选出的基础字集，见 `Scheme::primary_words`；
+/// 成语编码时为选取范围内的成语，见 `Scheme::primary_idioms`。
+pub(super) fn generate_charset_from_seed<T: Clone>(
+    seed: [u8; 32],
+    words: &[T],
+    size: usize,
+) -> Result<Vec<T>> {
+    let mut chacha_rng = ChaCha20Rng::from_seed(seed);
 
-    if count <= 500 || count + start > 8105 {
-        bail!(
-            "count({}) must be greater than 500 and last index({}) must not exceed 8105",
-            count,
-            count + start
-        );
+    let charset = words
+        .choose_multiple(&mut chacha_rng, size)
+        .cloned()
+        .collect::<Vec<T>>();
+    if charset.len() != size {
+        return Err(Error::InvalidParams(format!(
+            "primary charset must have at least {} characters, but got {}",
+            size,
+            words.len()
+        )));
     }
+    Ok(charset)
+}
 
-    let words = read_8105_slice(start, count)?;
-
-    let sha_seed = Sha256::digest(text_in_heart.as_bytes());
-    let mut chacha_rng = ChaCha20Rng::from_seed(sha_seed.into());
+/// 去掉形近字：每组形近字只保留在 `words` 中最先出现的一个，其余的字保持原来的顺序
+///
+/// 心印字表中不会同时出现 `己` 和 `已`，手抄时写得不够清楚也不会被认成另一个助记字。
+pub(super) fn exclude_confusables(words: &[char]) -> Vec<char> {
+    let mut seen_groups = Vec::new();
+    words
+        .iter()
+        .copied()
+        .filter(|&ch| match groups().find(|group| group.contains(ch)) {
+            Some(group) if seen_groups.contains(&group) => false,
+            Some(group) => {
+                seen_groups.push(group);
+                true
+            }
+            None => true,
+        })
+        .collect()
+}
 
-    let words_256 = words
-        .choose_multiple(&mut chacha_rng, 256)
-        .cloned()
-        .collect::<Vec<char>>();
-    if words_256.len() != 256 {
-        bail!("expected 256 characters, but got {}", words_256.len());
-    }
-    Ok(words_256)
+/// 去掉同音字：按 `words` 的顺序，与已保留的字有相同读音的字都去掉，多音字的每个读音都算在内
+///
+/// 口述助记字时听到的读音只对应一个字；没有收录拼音的字也会被去掉。
+pub(super) fn exclude_homophones(words: &[char], homophones: Homophones) -> Vec<char> {
+    let table = pinyin_table();
+    let mut seen = Vec::new();
+    words
+        .iter()
+        .copied()
+        .filter(|ch| {
+            let readings = table
+                .get(ch)
+                .into_iter()
+                .flatten()
+                .map(|&pinyin| match homophones {
+                    Homophones::DistinctSyllables => split_tone(pinyin).0,
+                    _ => pinyin,
+                })
+                .collect::<Vec<&str>>();
+            if readings.is_empty() || readings.iter().any(|r| seen.contains(r)) {
+                return false;
+            }
+            seen.extend(readings);
+            true
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::primary::PrimaryCharset;
     use super::*;
 
     #[test]
     fn test_read_8105_slice() {
-        let res = read_8105_slice(6, 666);
+        let res = PrimaryCharset::Tongyong8105.slice(6, 666);
         assert!(res.is_ok());
         let chars = res.unwrap();
         assert_eq!(chars[0], '厂');
@@ -77,7 +103,8 @@ mod tests {
 
     #[test]
     fn test_generate() {
-        let res = generate_256_words("hello", 6, 666);
+        let words = PrimaryCharset::Tongyong8105.slice(6, 666).unwrap();
+        let res = generate_256_words("hello", &words);
         assert!(res.is_ok());
         let chars = res.unwrap();
         assert_eq!(chars[0], '勾');
@@ -86,4 +113,106 @@ mod tests {
         assert_eq!(chars[255], '它');
         assert_eq!(chars.len(), 256);
     }
+
+    #[test]
+    fn test_exclude_confusables() {
+        let words = PrimaryCharset::Tongyong8105.chars().unwrap();
+        let excluded = exclude_confusables(&words);
+        let removed = groups()
+            .map(|group| group.chars().count() - 1)
+            .sum::<usize>();
+        assert_eq!(excluded.len(), words.len() - removed);
+        for group in groups() {
+            let kept = group
+                .chars()
+                .filter(|ch| excluded.contains(ch))
+                .collect::<Vec<char>>();
+            assert_eq!(kept.len(), 1, "group `{}`", group);
+        }
+        // 保留一级字中笔画最少、最先出现的字
+        assert!(excluded.contains(&'己') && !excluded.contains(&'已'));
+        assert_eq!(
+            exclude_confusables(&['问', '末', '抖', '未']),
+            vec!['问', '末', '抖']
+        );
+    }
+
+    #[test]
+    fn test_exclude_homophones() {
+        let words = ['力', '立', '历', '利', '李', '行', '航', '型'];
+        assert_eq!(
+            exclude_homophones(&words, Homophones::DistinctTones),
+            vec!['力', '李', '行']
+        );
+        assert_eq!(
+            exclude_homophones(&words, Homophones::DistinctSyllables),
+            vec!['力', '行']
+        );
+        // 同音字只保留一个，后一个字的次要读音与前一个字相同时也去掉
+        for pair in [
+            ['驯', '训'],
+            ['训', '驯'],
+            ['矮', '蔼'],
+            ['癌', '皑'],
+            ['罢', '爸'],
+            ['蟆', '麻'],
+            ['狸', '离'],
+            ['犸', '马'],
+        ] {
+            assert_eq!(
+                exclude_homophones(&pair, Homophones::DistinctTones),
+                vec![pair[0]],
+                "`{:?}`",
+                pair
+            );
+        }
+        assert_eq!(
+            exclude_homophones(&['矮', '爱', '癌'], Homophones::DistinctTones),
+            vec!['矮', '爱', '癌']
+        );
+
+        // 默认范围能选出 256 个以上声调不同的字，不论声调时至少要用全部一级字
+        let words = PrimaryCharset::Tongyong8105.slice(6, 666).unwrap();
+        assert_eq!(
+            exclude_homophones(&words, Homophones::DistinctTones).len(),
+            349
+        );
+        assert_eq!(
+            exclude_homophones(&words, Homophones::DistinctSyllables).len(),
+            205
+        );
+        let words = PrimaryCharset::Tongyong8105.slice(1, 3500).unwrap();
+        assert_eq!(
+            exclude_homophones(&words, Homophones::DistinctSyllables).len(),
+            323
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_homophone_free() {
+        use crate::{
+            Homophones, Scheme, SchemeVersion, generate_words32_with, import_words32_pinyin,
+            pinyin_of,
+        };
+
+        let sk = [37u8; 32];
+        let scheme = Scheme {
+            homophones: Homophones::DistinctSyllables,
+            ..Scheme::new(SchemeVersion::V2, 1, 8104)
+        };
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+
+        // 没有校验字、不带声调也不会有同音字
+        let pinyin = words
+            .chars()
+            .skip(1)
+            .map(|ch| pinyin_of(ch)[0].trim_end_matches(char::is_numeric))
+            .collect::<Vec<&str>>()
+            .join(" ");
+        assert_eq!(
+            import_words32_pinyin(&pinyin, "不立文字", &scheme).unwrap(),
+            sk
+        );
+    }
 }
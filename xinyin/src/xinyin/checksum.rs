@@ -0,0 +1,84 @@
+use sha2::{Digest, Sha256};
+
+/// 校验字最多的个数
+pub const MAX_CHECKSUM_WORDS: usize = 4;
+
+/// 计算加密密钥的校验字节
+///
+/// 取 SHA256(加密密钥) 的前 `n` 个字节，每个字节和密钥字节一样编码为心印字表中的一个字。
+/// 校验只覆盖已经写在助记字里的密文，不会泄露心印或原始密钥。
+pub(super) fn checksum(encrypted_key: &[u8], n: usize) -> Vec<u8> {
+    Sha256::digest(encrypted_key)[..n].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let key = [7u8; 32];
+        assert_eq!(checksum(&key, 0), Vec::<u8>::new());
+        assert_eq!(checksum(&key, 2), checksum(&key, MAX_CHECKSUM_WORDS)[..2]);
+
+        let mut typo = key;
+        typo[5] = 8;
+        assert_ne!(checksum(&key, 1), checksum(&typo, 1));
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_checksum() {
+        use crate::{Error, Scheme, generate_words32_with, import_words32, import_words32_with};
+
+        let scheme = Scheme {
+            checksum: 2,
+            ..Scheme::v1(6, 666)
+        };
+        let sk = [9u8; 32];
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+        assert_eq!(words.chars().count(), 34);
+        assert_eq!(
+            import_words32_with(&words, "不立文字", &scheme).unwrap(),
+            sk
+        );
+
+        let mut chars = words.chars().collect::<Vec<char>>();
+        chars.swap(0, 1);
+        let typo = chars.into_iter().collect::<String>();
+        let err = import_words32_with(&typo, "不立文字", &scheme).unwrap_err();
+        assert_eq!(err, Error::ChecksumMismatch);
+
+        // 校验字个数以方案为准，多出或缺少的字不会被当作校验字
+        let err = import_words32(&words, "不立文字", 6, 666).unwrap_err();
+        assert_eq!(err.code(), "wrong_length");
+        let extra = format!("{}问", words);
+        let err = import_words32_with(&extra, "不立文字", &scheme).unwrap_err();
+        assert_eq!(err.code(), "wrong_length");
+        let short = words.chars().take(33).collect::<String>();
+        let err = import_words32_with(&short, "不立文字", &scheme).unwrap_err();
+        assert_eq!(err.code(), "wrong_length");
+    }
+
+    #[test]
+    #[cfg(feature = "import")]
+    fn test_import_words_rejects_out_of_range_checksum() {
+        use crate::{Scheme, import_words};
+
+        // 校验字个数超出上限时返回错误，不能因为截取摘要而 panic
+        let words = "问".repeat(80);
+        for scheme in [
+            Scheme {
+                checksum: 40,
+                ..Scheme::v1(6, 666)
+            },
+            Scheme {
+                parity: 33,
+                ..Scheme::v1(6, 666)
+            },
+        ] {
+            let err = import_words(&words, "x", &scheme).unwrap_err();
+            assert_eq!(err.code(), "invalid_params");
+        }
+    }
+}
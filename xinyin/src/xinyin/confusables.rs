@@ -0,0 +1,91 @@
+// 形近字提示只在 import 中使用，generate 只用到 `groups`
+#![cfg_attr(not(feature = "import"), allow(dead_code))]
+
+const CONFUSABLES: &str = include_str!("../../confusables.txt");
+
+/// 所有形近字组
+pub(super) fn groups() -> impl Iterator<Item = &'static str> {
+    CONFUSABLES
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// 与 `ch` 形近的字，不含 `ch` 本身
+pub fn confusables_of(ch: char) -> Vec<char> {
+    groups()
+        .filter(|group| group.contains(ch))
+        .flat_map(str::chars)
+        .filter(|&c| c != ch)
+        .collect()
+}
+
+/// 在心印字表中找出与 `ch` 形近的字，作为“您是不是要输入”的候选
+pub(super) fn suggest(ch: char, charset: &[char]) -> Vec<char> {
+    confusables_of(ch)
+        .into_iter()
+        .filter(|c| charset.contains(c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confusables_in_8105() {
+        let words = include_str!("../../8105.bin");
+        for group in groups() {
+            assert!(group.chars().count() >= 2, "group `{}` is too small", group);
+            for ch in group.chars() {
+                assert!(words.contains(ch), "`{}` is not in 8105.bin", ch);
+                assert_eq!(
+                    groups().filter(|g| g.contains(ch)).count(),
+                    1,
+                    "`{}` appears in more than one group",
+                    ch
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(confusables_of('己'), vec!['已', '巳']);
+        assert_eq!(suggest('己', &['巳', '问', '抖']), vec!['巳']);
+        assert!(suggest('问', &['巳', '抖']).is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_confusable_free() {
+        use crate::{Scheme, SchemeVersion, generate_words32_with, import_words32_with};
+
+        let sk = [31u8; 32];
+        let scheme = Scheme {
+            confusable_free: true,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        let charset = scheme.derive("不立文字").unwrap().charset;
+        for group in groups() {
+            assert!(group.chars().filter(|ch| charset.contains(ch)).count() <= 1);
+        }
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+        assert_eq!(
+            import_words32_with(&words, "不立文字", &scheme).unwrap(),
+            sk
+        );
+        // 不去掉形近字时字表不同，无法还原
+        let plain = Scheme::new(SchemeVersion::V2, 6, 666);
+        assert_ne!(
+            import_words32_with(&words, "不立文字", &plain).ok(),
+            Some(sk)
+        );
+
+        let v1 = Scheme {
+            confusable_free: true,
+            ..Scheme::v1(6, 666)
+        };
+        assert!(generate_words32_with("不立文字", &v1, Some(&sk)).is_err());
+    }
+}
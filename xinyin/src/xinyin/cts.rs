@@ -0,0 +1,127 @@
+//! AES-256-CBC 密文挪用（ciphertext stealing，NIST SP 800-38A 附录 CS1）
+//!
+//! 密文和明文等长，任意长度的密钥都不需要填充，助记字的字数就是密钥的字节数。
+//! 长度是 16 的整数倍时与普通 CBC 完全一致，32 字节密钥的助记字和最初实现相同。
+//! 不足一个分组（16 字节）时退化为与 E(IV) 异或。
+
+// 加密只在 generate 中使用，解密只在 import 中使用
+#![cfg_attr(not(all(feature = "generate", feature = "import")), allow(dead_code))]
+
+use aes::{
+    Aes256, Block,
+    cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit},
+};
+
+const BLOCK: usize = 16;
+
+fn xor(block: &mut [u8], other: &[u8]) {
+    for (b, o) in block.iter_mut().zip(other) {
+        *b ^= o;
+    }
+}
+
+/// 就地加密 `data`，`data` 不能为空
+pub(super) fn encrypt(key: &[u8; 32], iv: &[u8; 16], data: &mut [u8]) {
+    let cipher = Aes256::new(key.into());
+
+    if data.len() < BLOCK {
+        let mut pad = Block::from(*iv);
+        cipher.encrypt_block(&mut pad);
+        xor(data, &pad);
+        return;
+    }
+
+    // 最后一个分组不完整时，倒数第二个分组的密文只保留前 `tail` 个字节
+    let tail = data.len() % BLOCK;
+    let full = data.len() - tail;
+
+    let mut prev = Block::from(*iv);
+    for chunk in data[..full].chunks_exact_mut(BLOCK) {
+        xor(&mut prev, chunk);
+        cipher.encrypt_block(&mut prev);
+        chunk.copy_from_slice(&prev);
+    }
+
+    if tail > 0 {
+        xor(&mut prev, &data[full..]);
+        cipher.encrypt_block(&mut prev);
+        data[full - BLOCK + tail..].copy_from_slice(&prev);
+    }
+}
+
+/// 就地解密 [`encrypt`] 加密的 `data`
+pub(super) fn decrypt(key: &[u8; 32], iv: &[u8; 16], data: &mut [u8]) {
+    let cipher = Aes256::new(key.into());
+
+    if data.len() < BLOCK {
+        let mut pad = Block::from(*iv);
+        cipher.encrypt_block(&mut pad);
+        xor(data, &pad);
+        return;
+    }
+
+    let tail = data.len() % BLOCK;
+    let full = data.len() - tail;
+
+    if tail > 0 {
+        // 还原倒数第二个分组完整的密文 X 和最后一个不完整分组的明文
+        let stolen = full - BLOCK;
+        let mut last = Block::default();
+        last.copy_from_slice(&data[stolen + tail..]);
+        cipher.decrypt_block(&mut last);
+
+        let mut x = last;
+        x[..tail].copy_from_slice(&data[stolen..stolen + tail]);
+        xor(&mut last[..tail], &x[..tail]);
+
+        data[stolen..full].copy_from_slice(&x);
+        data[full..].copy_from_slice(&last[..tail]);
+    }
+
+    let mut prev = Block::from(*iv);
+    for chunk in data[..full].chunks_exact_mut(BLOCK) {
+        let mut block = Block::default();
+        block.copy_from_slice(chunk);
+        let next = block;
+        cipher.decrypt_block(&mut block);
+        xor(&mut block, &prev);
+        chunk.copy_from_slice(&block);
+        prev = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = [1u8; 32];
+        let iv = [2u8; 16];
+        for len in [1, 10, 15, 16, 17, 24, 31, 32, 33, 64, 100] {
+            let plain = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+            let mut data = plain.clone();
+            encrypt(&key, &iv, &mut data);
+            assert_eq!(data.len(), len);
+            assert_ne!(data, plain);
+            decrypt(&key, &iv, &mut data);
+            assert_eq!(data, plain);
+        }
+    }
+
+    #[test]
+    fn test_same_as_cbc_on_whole_blocks() {
+        use aes::cipher::{BlockModeEncrypt, KeyIvInit, block_padding::NoPadding};
+
+        let key = [3u8; 32];
+        let iv = [4u8; 16];
+        let mut expected = [5u8; 32];
+        cbc::Encryptor::<Aes256>::new(&key.into(), &iv.into())
+            .encrypt_padded::<NoPadding>(&mut expected, 32)
+            .unwrap();
+
+        let mut data = [5u8; 32];
+        encrypt(&key, &iv, &mut data);
+        assert_eq!(data, expected);
+    }
+}
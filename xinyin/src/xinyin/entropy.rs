@@ -0,0 +1,198 @@
+//! 随机密钥：系统随机数与用户提供的熵混合
+//!
+//! 老旧设备或虚拟机上的系统随机数不一定可信。用户可以额外提供骰子点数、抛硬币的结果或随手敲出的文本，
+//! 与系统随机数一起经 SHA256 混合成密钥：只要两者有一个不可预测，密钥就不可预测。
+//! 99 次骰子（每次 log2(6) ≈ 2.585 位）或 256 次硬币即可单独提供约 256 位熵。
+
+use rand::TryCryptoRng;
+use sha2::Digest;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{Error, Result};
+
+/// 单独提供约 256 位熵（255.9 位）需要的骰子次数
+pub const DICE_ROLLS_FOR_256_BITS: usize = 99;
+/// 单独提供 256 位熵需要的硬币次数
+pub const COIN_FLIPS_FOR_256_BITS: usize = 256;
+
+/// 用户提供的熵
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserEntropy {
+    /// 骰子点数，每个为 1–6
+    Dice(Vec<u8>),
+    /// 抛硬币的结果，`true` 为正面
+    Coins(Vec<bool>),
+    /// 任意文本，如随手敲出的字符
+    Text(String),
+}
+
+impl UserEntropy {
+    /// 解析骰子点数，如 `3516`、`3 5 1 6`，可以用空格或逗号分隔
+    pub fn parse_dice(input: &str) -> Result<Self> {
+        let mut rolls = Vec::new();
+        for (i, ch) in input
+            .chars()
+            .filter(|ch| !ch.is_whitespace() && !matches!(ch, ',' | '，'))
+            .enumerate()
+        {
+            match ch.to_digit(10) {
+                Some(roll @ 1..=6) => rolls.push(roll as u8),
+                _ => {
+                    return Err(Error::InvalidParams(format!(
+                        "dice roll `{}` at {} must be between 1 and 6",
+                        ch, i
+                    )));
+                }
+            }
+        }
+        if rolls.is_empty() {
+            return Err(Error::InvalidParams("no dice rolls".to_string()));
+        }
+        Ok(UserEntropy::Dice(rolls))
+    }
+
+    /// 解析抛硬币的结果：`H`、`1`、`正` 为正面，`T`、`0`、`反` 为反面，可以用空格或逗号分隔
+    pub fn parse_coins(input: &str) -> Result<Self> {
+        let mut flips = Vec::new();
+        for (i, ch) in input
+            .chars()
+            .filter(|ch| !ch.is_whitespace() && !matches!(ch, ',' | '，'))
+            .enumerate()
+        {
+            match ch {
+                'H' | 'h' | '1' | '正' => flips.push(true),
+                'T' | 't' | '0' | '反' => flips.push(false),
+                _ => {
+                    return Err(Error::InvalidParams(format!(
+                        "coin flip `{}` at {} must be H or T",
+                        ch, i
+                    )));
+                }
+            }
+        }
+        if flips.is_empty() {
+            return Err(Error::InvalidParams("no coin flips".to_string()));
+        }
+        Ok(UserEntropy::Coins(flips))
+    }
+
+    /// 骰子和硬币最多能提供的熵位数；文本无法估计，返回 `None`
+    pub fn bits(&self) -> Option<f64> {
+        match self {
+            UserEntropy::Dice(rolls) => Some(rolls.len() as f64 * 6f64.log2()),
+            UserEntropy::Coins(flips) => Some(flips.len() as f64),
+            UserEntropy::Text(_) => None,
+        }
+    }
+
+    /// 参与混合的字节：类型和内容，不同类型的输入不会混淆
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            UserEntropy::Dice(rolls) => [b"dice:", &rolls[..]].concat(),
+            UserEntropy::Coins(flips) => [
+                &b"coins:"[..],
+                &flips
+                    .iter()
+                    .map(|&head| u8::from(head))
+                    .collect::<Vec<u8>>(),
+            ]
+            .concat(),
+            UserEntropy::Text(text) => [b"text:", text.as_bytes()].concat(),
+        }
+    }
+}
+
+/// 生成 32 字节的随机密钥
+///
+/// 从 `rng`（通常为 `OsRng`，测试时可以换成固定种子的 RNG）取 32 字节；
+/// 有 `entropy` 时再与之一起经 SHA256 混合，没有时直接使用这 32 字节。返回的密钥在释放时清零。
+pub fn generate_secret_key<R: TryCryptoRng + ?Sized>(
+    rng: &mut R,
+    entropy: Option<&UserEntropy>,
+) -> Result<Zeroizing<[u8; 32]>> {
+    let mut secret_key = Zeroizing::new([0u8; 32]);
+    rng.try_fill_bytes(secret_key.as_mut())
+        .map_err(|err| Error::RandomFailure(format!("rng: {}", err)))?;
+    if let Some(entropy) = entropy {
+        let input = Zeroizing::new(
+            [
+                &secret_key[..],
+                b"-mixed-with-xinyin-user-entropy-",
+                &Zeroizing::new(entropy.to_bytes()),
+            ]
+            .concat(),
+        );
+        let mut mixed = sha2::Sha256::digest(&*input);
+        secret_key.copy_from_slice(&mixed);
+        mixed.zeroize();
+    }
+    Ok(secret_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_parse_entropy() {
+        assert_eq!(
+            UserEntropy::parse_dice("3 5,1，6\n2").unwrap(),
+            UserEntropy::Dice(vec![3, 5, 1, 6, 2])
+        );
+        assert!(UserEntropy::parse_dice("3517").is_err());
+        assert!(UserEntropy::parse_dice("30").is_err());
+        assert!(UserEntropy::parse_dice(" ").is_err());
+        let dice = UserEntropy::parse_dice(&"6".repeat(DICE_ROLLS_FOR_256_BITS)).unwrap();
+        assert!(dice.bits().unwrap() > 255.0);
+
+        assert_eq!(
+            UserEntropy::parse_coins("HhT 1 0 正反").unwrap(),
+            UserEntropy::Coins(vec![true, true, false, true, false, true, false])
+        );
+        assert!(UserEntropy::parse_coins("HTX").is_err());
+        assert_eq!(UserEntropy::Text("abc".to_string()).bits(), None);
+    }
+
+    #[test]
+    fn test_generate_secret_key() {
+        let rng = || ChaCha20Rng::from_seed([7; 32]);
+        let plain = generate_secret_key(&mut rng(), None).unwrap();
+        assert_eq!(plain, generate_secret_key(&mut rng(), None).unwrap());
+
+        // 混合后的密钥与系统随机数和用户的熵都有关，不同类型的输入不会混淆
+        let dice = UserEntropy::parse_dice("1234").unwrap();
+        let mixed = generate_secret_key(&mut rng(), Some(&dice)).unwrap();
+        assert_ne!(mixed, plain);
+        assert_eq!(mixed, generate_secret_key(&mut rng(), Some(&dice)).unwrap());
+        let text = UserEntropy::Text("\u{1}\u{2}\u{3}\u{4}".to_string());
+        assert_ne!(mixed, generate_secret_key(&mut rng(), Some(&text)).unwrap());
+        assert_ne!(
+            mixed,
+            generate_secret_key(&mut ChaCha20Rng::from_seed([8; 32]), Some(&dice)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_recovery_card_with_rng() {
+        use crate::{Scheme, SchemeVersion, generate_recovery_card_with_rng, generate_words};
+
+        let scheme = Scheme::new(SchemeVersion::LATEST, 1000, 512);
+        let dice = UserEntropy::parse_dice(&"3".repeat(DICE_ROLLS_FOR_256_BITS)).unwrap();
+        let card = generate_recovery_card_with_rng(
+            "星辰大海，心自无疆。",
+            &scheme,
+            &mut ChaCha20Rng::from_seed([7; 32]),
+            Some(&dice),
+        )
+        .unwrap();
+        let secret_key =
+            generate_secret_key(&mut ChaCha20Rng::from_seed([7; 32]), Some(&dice)).unwrap();
+        assert_eq!(
+            card.words,
+            generate_words("星辰大海，心自无疆。", &scheme, &*secret_key).unwrap()
+        );
+        assert_eq!(card.scheme, scheme);
+    }
+}
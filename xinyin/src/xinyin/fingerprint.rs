@@ -0,0 +1,149 @@
+//! 心印指纹：核对生成和导入时输入的是不是同一句心印
+//!
+//! 任何一句话都能生成助记字，生成时输错的心印会得到一份无法用本来那句话还原的备份。
+//! 生成和导入时都显示一个简短的指纹：心印字表中的两个字（成语编码时为一条成语），加上一种颜色和一种动物。
+//! 指纹由 Argon2 派生的 AES 密钥经 SHA256 得出，从指纹猜测心印与从助记字猜测一样，每猜一次都要计算一次 Argon2。
+
+use std::fmt;
+
+use sha2::Digest;
+
+use crate::Result;
+
+use super::scheme::Scheme;
+
+/// 颜色：emoji 圆点和名称
+const COLORS: &[(&str, &str)] = &[
+    ("🔴", "红"),
+    ("🟠", "橙"),
+    ("🟡", "黄"),
+    ("🟢", "绿"),
+    ("🔵", "蓝"),
+    ("🟣", "紫"),
+    ("🟤", "棕"),
+    ("⚫", "黑"),
+    ("⚪", "白"),
+];
+
+/// 动物：十二生肖和另外四种，emoji 和名称
+const ANIMALS: &[(&str, &str)] = &[
+    ("🐭", "鼠"),
+    ("🐮", "牛"),
+    ("🐯", "虎"),
+    ("🐰", "兔"),
+    ("🐲", "龙"),
+    ("🐍", "蛇"),
+    ("🐴", "马"),
+    ("🐑", "羊"),
+    ("🐵", "猴"),
+    ("🐔", "鸡"),
+    ("🐶", "狗"),
+    ("🐷", "猪"),
+    ("🐼", "熊猫"),
+    ("🦊", "狐狸"),
+    ("🐸", "青蛙"),
+    ("🐧", "企鹅"),
+];
+
+/// 心印指纹，文本形式如 `🟢🐼 潮映`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeartprintFingerprint {
+    /// 心印字表中的两个字，成语编码时为心印成语表中的一条成语
+    pub words: String,
+    pub color_emoji: &'static str,
+    /// 颜色名称，如 `绿`
+    pub color: &'static str,
+    pub animal_emoji: &'static str,
+    /// 动物名称，如 `熊猫`
+    pub animal: &'static str,
+}
+
+impl fmt::Display for HeartprintFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{} {}",
+            self.color_emoji, self.animal_emoji, self.words
+        )
+    }
+}
+
+/// 按 `scheme` 由心印派生出指纹，与生成、导入助记字时的派生过程相同，需要计算一次 Argon2
+pub fn heartprint_fingerprint(
+    txt_in_heart: &str,
+    scheme: &Scheme,
+) -> Result<HeartprintFingerprint> {
+    let derived = scheme.derive(txt_in_heart)?;
+    let digest = sha2::Sha256::digest([&derived.aes_key[..], b"-as-xinyin-fingerprint"].concat());
+    // 每项取 8 字节对 len 取余，偏差小于 2^-50
+    let index = |i: usize, len: usize| {
+        let bytes = <[u8; 8]>::try_from(&digest[i..i + 8]).expect("8 bytes");
+        (u64::from_be_bytes(bytes) % len as u64) as usize
+    };
+
+    let words = if scheme.idioms {
+        derived.idioms[index(0, derived.idioms.len())].to_string()
+    } else {
+        // 第二个字从其余的字中选，两个字不会相同
+        let len = derived.charset.len();
+        let first = index(0, len);
+        let second = (first + 1 + index(8, len - 1)) % len;
+        [derived.charset[first], derived.charset[second]]
+            .iter()
+            .collect()
+    };
+    let (color_emoji, color) = COLORS[index(16, COLORS.len())];
+    let (animal_emoji, animal) = ANIMALS[index(24, ANIMALS.len())];
+    Ok(HeartprintFingerprint {
+        words,
+        color_emoji,
+        color,
+        animal_emoji,
+        animal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SchemeVersion;
+
+    #[test]
+    fn test_heartprint_fingerprint() {
+        let scheme = Scheme::new(SchemeVersion::LATEST, 1000, 512);
+        let fingerprint = heartprint_fingerprint("星辰大海，心自无疆。", &scheme).unwrap();
+        // 指纹的算法与方案版本一样不能再改变，否则用户记下的指纹对不上
+        assert_eq!(fingerprint.to_string(), "⚫🐶 挤忿");
+        assert_eq!((fingerprint.color, fingerprint.animal), ("黑", "狗"));
+
+        // 规范化后相同的心印指纹相同，输错一个字指纹不同
+        assert_eq!(
+            heartprint_fingerprint(" 星辰大海,心自无疆. ", &scheme).unwrap(),
+            fingerprint
+        );
+        assert_ne!(
+            heartprint_fingerprint("星辰大海，心字无疆。", &scheme).unwrap(),
+            fingerprint
+        );
+        // 方案参数不同，指纹也不同
+        let labeled = Scheme {
+            label: "1".to_string(),
+            ..scheme.clone()
+        };
+        assert_ne!(
+            heartprint_fingerprint("星辰大海，心自无疆。", &labeled).unwrap(),
+            fingerprint
+        );
+
+        let idioms = Scheme {
+            idioms: true,
+            radix: 4096,
+            ..Scheme::new(SchemeVersion::LATEST, 1, 8191)
+        };
+        let fingerprint = heartprint_fingerprint("星辰大海，心自无疆。", &idioms).unwrap();
+        assert_eq!(fingerprint.words.chars().count(), 4);
+
+        let v1 = heartprint_fingerprint("星辰大海，心自无疆。", &Scheme::v1(1000, 512)).unwrap();
+        assert_eq!(v1.words.chars().count(), 2);
+    }
+}
@@ -1,61 +1,161 @@
-use super::charset_256::generate_256_words;
+use super::card::RecoveryCard;
+use super::cts;
+use super::entropy::{UserEntropy, generate_secret_key};
+use super::scheme::Scheme;
 
-/// 生成心印密钥
+/// 按 v1 方案生成心印密钥，助记字没有版本标记字，与 [`import_words32`](crate::import_words32) 对应
+///
+/// v1 的字表派生较弱，新生成的心印密钥应使用 [`generate_words32_with`] 和最新版本的方案。
 /// * `txt_in_heart`: 心印文本
-/// * `start`: 1-based index, must be between 1 and 7600
-/// * `count`: must be greater than 500
+/// * `start`: 1-based index into the 8105-character table, must be between 1 and 7600
+/// * `count`: must be greater than 500, and `start + count` must not exceed 8105
 /// * `sk`: optional secret key, if not provided, a random one will be generated
+///
+/// v1 只使用《通用规范汉字表》，不支持其它基础字集和成语编码，这些方案的范围限制见 [`Error::InvalidRange`]。
 pub fn generate_words32(
     txt_in_heart: &str,
     start: usize,
     count: usize,
     sk: Option<&[u8]>,
 ) -> Result<String> {
-    let words = generate_256_words(txt_in_heart, start, count)
-        .context("failed to generate charset256 words")?;
-
-    let mut secret_key = match sk {
-        Some(sk) => sk
-            .try_into()
-            .map_err(|_| anyhow::anyhow!("secret key must be 32 bytes"))?,
-        None => {
-            let mut secret_key = [0u8; 32];
-            OsRng
-                .try_fill_bytes(&mut secret_key)
-                .context("osrng failed when generating xinyin secret key")?;
-            secret_key
-        }
+    generate_words32_with(txt_in_heart, &Scheme::v1(start, count), sk)
+}
+
+/// 按指定方案生成心印密钥，v1 之后的版本会在助记字最前面加上版本标记字
+/// * `txt_in_heart`: 心印文本
+/// * `scheme`: 心印方案
+/// * `sk`: optional secret key, if not provided, a random one will be generated
+pub fn generate_words32_with(
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    sk: Option<&[u8]>,
+) -> Result<String> {
+    let secret_key = match sk {
+        Some(sk) => Zeroizing::new(
+            <[u8; 32]>::try_from(sk)
+                .map_err(|_| Error::WrongLength("secret key must be 32 bytes".to_string()))?,
+        ),
+        None => generate_secret_key(&mut OsRng, None)?,
     };
 
-    encrypt_xinyin_key(txt_in_heart, &mut secret_key)?;
+    generate_words(txt_in_heart, scheme, &*secret_key)
+}
 
-    let mut key_words: Vec<char> = Vec::new();
-    for (_, &byte) in secret_key.iter().enumerate() {
-        let index = byte as usize % words.len();
-        key_words.push(words[index]);
+/// 按指定方案把任意长度的密钥编码为助记字
+///
+/// 密钥有多少字节，助记字（不含版本标记、校验字和纠错字）就有多少个字，
+/// 如 16 字节的 BIP39 熵、64 字节的 Solana 密钥对或 TOTP 种子。
+/// 导入时按字数还原长度，需要用同样的 `scheme.checksum`，见 [`import_words`](crate::import_words)。
+pub fn generate_words(txt_in_heart: &str, scheme: &Scheme, secret: &[u8]) -> Result<String> {
+    if secret.is_empty() {
+        return Err(Error::WrongLength("secret must not be empty".to_string()));
     }
 
-    Ok(key_words.into_iter().collect())
+    scheme.check_lengths(Some(secret.len()))?;
+
+    let derived = scheme.derive(txt_in_heart)?;
+
+    // 加密前这里是密钥的明文副本
+    let mut encrypted = Zeroizing::new(secret.to_vec());
+    cts::encrypt(&derived.aes_key, &derived.aes_iv, &mut encrypted);
+
+    scheme.encode(&derived, &encrypted)
+}
+
+/// 按指定方案生成心印密钥，返回包含助记字和全部方案参数的恢复卡
+///
+/// `sk` 可以是任意长度的密钥，不提供时随机生成 32 字节密钥。
+pub fn generate_recovery_card(
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    sk: Option<&[u8]>,
+) -> Result<RecoveryCard> {
+    let words = match sk {
+        Some(sk) => generate_words(txt_in_heart, scheme, sk)?,
+        None => generate_words32_with(txt_in_heart, scheme, None)?,
+    };
+    Ok(RecoveryCard::new(scheme.clone(), words))
 }
 
-use super::utils::generate_cbc_key_iv;
-use aes::{
-    Aes256,
-    cipher::{BlockModeEncrypt, KeyIvInit, block_padding::NoPadding},
-};
-use anyhow::{Context, Result};
-use cbc::Encryptor;
-use rand::{TryRngCore, rngs::OsRng};
+/// 用 `rng` 生成 32 字节的随机密钥，有 `entropy` 时与用户提供的熵混合，返回恢复卡
+///
+/// 见 [`generate_secret_key`]；`rng` 为固定种子的 RNG 时结果是确定的，便于测试。
+pub fn generate_recovery_card_with_rng<R: TryCryptoRng + ?Sized>(
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    rng: &mut R,
+    entropy: Option<&UserEntropy>,
+) -> Result<RecoveryCard> {
+    let secret_key = generate_secret_key(rng, entropy)?;
+    let words = generate_words(txt_in_heart, scheme, &*secret_key)?;
+    Ok(RecoveryCard::new(scheme.clone(), words))
+}
+
+use crate::{Error, Result};
+use rand::{TryCryptoRng, rngs::OsRng};
+use zeroize::Zeroizing;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-type Aes256CbcEnc = Encryptor<Aes256>;
+    #[test]
+    fn test_generate_words32_v1() {
+        let sk = [
+            194, 124, 163, 194, 143, 43, 107, 102, 250, 172, 57, 40, 79, 56, 100, 215, 145, 185,
+            195, 196, 3, 98, 199, 30, 111, 112, 46, 51, 223, 108, 157, 199,
+        ];
+        // v1 没有版本标记字，与旧版生成的助记字相同
+        assert_eq!(
+            generate_words32("不立文字", 6, 666, Some(&sk)).unwrap(),
+            "问抖廷亿丙驯弗付屹节尔力麦叮计寻右右问丑幻坏丙忆天闪父旬力扑抖延"
+        );
+        assert!(generate_words32("不立文字", 6, 666, Some(&sk[1..])).is_err());
+        assert!(generate_words32("不立文字", 7601, 501, None).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "import")]
+    fn test_import_words_variable_length() {
+        use crate::{SchemeVersion, import_words};
 
-fn encrypt_xinyin_key(txt_in_heart: &str, sk: &mut [u8; 32]) -> Result<()> {
-    let (aes_key, aes_iv) =
-        generate_cbc_key_iv(txt_in_heart).context("failed to encrypt xinyin secret key")?;
+        let scheme = Scheme {
+            checksum: 2,
+            parity: 4,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        for len in [10, 16, 24, 32, 64] {
+            let secret = (0..len).map(|i| i as u8 * 3).collect::<Vec<u8>>();
+            let words = generate_words("不立文字", &scheme, &secret).unwrap();
+            assert_eq!(words.chars().count(), 1 + len + 2 + 4);
+            assert_eq!(import_words(&words, "不立文字", &scheme).unwrap(), secret);
+        }
 
-    Aes256CbcEnc::new(&aes_key.into(), &aes_iv.into())
-        .encrypt_padded::<NoPadding>(sk, 32)
-        .context("failed to encrypt xinyin secret key")?;
+        // 32 字节密钥与 generate_words32 的结果相同
+        let sk = [7u8; 32];
+        assert_eq!(
+            generate_words("不立文字", &scheme, &sk).unwrap(),
+            generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap()
+        );
 
-    Ok(())
+        assert!(generate_words("不立文字", &scheme, &[]).is_err());
+        assert!(generate_words("不立文字", &scheme, &[1u8; 250]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "import")]
+    fn test_import_recovery_card_keypair() {
+        use crate::{SchemeVersion, import_recovery_card};
+
+        let keypair = [13u8; 64];
+        let scheme = Scheme {
+            checksum: 1,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        let card = generate_recovery_card("不立文字", &scheme, Some(&keypair)).unwrap();
+        assert_eq!(
+            import_recovery_card(&card, "不立文字").unwrap(),
+            keypair.to_vec()
+        );
+    }
 }
@@ -0,0 +1,74 @@
+//! GF(2^8) 有限域运算，既约多项式 x^8 + x^4 + x^3 + x^2 + 1 (0x11d)，生成元为 2
+
+// 编码只在 generate 中使用，纠错只在 import 中使用
+#![cfg_attr(not(all(feature = "generate", feature = "import")), allow(dead_code))]
+
+const PRIMITIVE: u16 = 0x11d;
+
+const TABLES: ([u8; 512], [u8; 256]) = {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= PRIMITIVE;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+    (exp, log)
+};
+
+const EXP: [u8; 512] = TABLES.0;
+const LOG: [u8; 256] = TABLES.1;
+
+pub(super) fn mul(x: u8, y: u8) -> u8 {
+    if x == 0 || y == 0 {
+        return 0;
+    }
+    EXP[LOG[x as usize] as usize + LOG[y as usize] as usize]
+}
+
+/// * `y`: must not be 0
+pub(super) fn div(x: u8, y: u8) -> u8 {
+    assert!(y != 0, "division by zero in GF(256)");
+    if x == 0 {
+        return 0;
+    }
+    EXP[LOG[x as usize] as usize + 255 - LOG[y as usize] as usize]
+}
+
+/// * `x`: must not be 0
+pub(super) fn inverse(x: u8) -> u8 {
+    div(1, x)
+}
+
+/// x 的 power 次幂，power 可以为负数
+pub(super) fn pow(x: u8, power: i32) -> u8 {
+    if x == 0 {
+        return 0;
+    }
+    EXP[(LOG[x as usize] as i32 * power).rem_euclid(255) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_ops() {
+        for x in 1..=255u8 {
+            assert_eq!(mul(x, inverse(x)), 1);
+            assert_eq!(div(mul(x, 0x53), 0x53), x);
+            assert_eq!(pow(x, -1), inverse(x));
+        }
+        assert_eq!(pow(2, 8), 0x1d);
+    }
+}
@@ -0,0 +1,384 @@
+//! 心印规范化：同一句心印换一种输入方式，也派生出同一个钱包
+//!
+//! 心印按字节参与哈希，全角和半角的逗号、macOS 输入的分解形式（NFD）、末尾多出的空格都会派生出另一个钱包。
+//! v3 起派生前先按以下步骤规范化心印：
+//!
+//! 1. Unicode NFC（规范组合）；
+//! 2. 全角字符换成半角，中文标点换成对应的英文标点，如 `，` 为 `,`、`。` 为 `.`、`“”` 为 `"`；
+//! 3. 去掉首尾的空白，中间连续的空白合并为一个空格；
+//! 4. 可选：去掉全部标点符号（Unicode 一般类别 P）和空白，见 `Scheme::strip_punctuation`。
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+const UNICODE_DATA: &str = include_str!("../../unicode-data.txt");
+
+/// 谚文音节按算法分解和组合，见 Unicode 标准第 3.12 节
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const N_COUNT: u32 = V_COUNT * T_COUNT;
+const S_COUNT: u32 = L_COUNT * N_COUNT;
+
+/// 中文标点对应的英文标点，全角字符另按码位换算
+const PUNCTUATION_FOLDS: &[(char, &str)] = &[
+    ('。', "."),
+    ('、', ","),
+    ('“', "\""),
+    ('”', "\""),
+    ('「', "\""),
+    ('」', "\""),
+    ('『', "\""),
+    ('』', "\""),
+    ('‘', "'"),
+    ('’', "'"),
+    ('【', "["),
+    ('】', "]"),
+    ('〔', "("),
+    ('〕', ")"),
+    ('《', "<"),
+    ('》', ">"),
+    ('〈', "<"),
+    ('〉', ">"),
+    ('—', "-"),
+    ('…', "..."),
+    ('｡', "."),
+    ('､', ","),
+    ('｢', "\""),
+    ('｣', "\""),
+];
+
+struct Tables {
+    /// 一层规范分解
+    decompositions: HashMap<char, Vec<char>>,
+    /// 两个字组合成的字
+    compositions: HashMap<(char, char), char>,
+    /// 组合类非 0 的区间，按码位排列
+    combining_classes: Vec<(char, char, u8)>,
+    /// 标点符号的区间，按码位排列
+    punctuation: Vec<(char, char)>,
+}
+
+static TABLES: LazyLock<Tables> = LazyLock::new(|| {
+    let hex = |s: &str| {
+        u32::from_str_radix(s, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .expect("invalid code point in unicode-data.txt")
+    };
+    let mut tables = Tables {
+        decompositions: HashMap::new(),
+        compositions: HashMap::new(),
+        combining_classes: Vec::new(),
+        punctuation: Vec::new(),
+    };
+    let mut excluded = Vec::new();
+    for line in UNICODE_DATA
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let fields = line.split(' ').collect::<Vec<&str>>();
+        match fields[0] {
+            "D" => {
+                let decomposition = fields[2..].iter().map(|s| hex(s)).collect();
+                tables.decompositions.insert(hex(fields[1]), decomposition);
+            }
+            "X" => excluded.push(hex(fields[1])),
+            "C" => tables.combining_classes.push((
+                hex(fields[1]),
+                hex(fields[2]),
+                fields[3].parse().expect("invalid combining class"),
+            )),
+            "P" => tables.punctuation.push((hex(fields[1]), hex(fields[2]))),
+            _ => panic!("unknown line `{}` in unicode-data.txt", line),
+        }
+    }
+    for (&ch, decomposition) in &tables.decompositions {
+        if let [first, second] = decomposition[..]
+            && !excluded.contains(&ch)
+        {
+            tables.compositions.insert((first, second), ch);
+        }
+    }
+    tables
+});
+
+fn combining_class(ch: char) -> u8 {
+    let ranges = &TABLES.combining_classes;
+    let i = ranges.partition_point(|&(_, end, _)| end < ch);
+    match ranges.get(i) {
+        Some(&(start, _, class)) if start <= ch => class,
+        _ => 0,
+    }
+}
+
+fn is_punctuation(ch: char) -> bool {
+    let ranges = &TABLES.punctuation;
+    let i = ranges.partition_point(|&(_, end)| end < ch);
+    ranges.get(i).is_some_and(|&(start, _)| start <= ch)
+}
+
+/// 完全分解 `ch`，追加到 `out`
+fn decompose_into(ch: char, out: &mut Vec<char>) {
+    let s_index = (ch as u32).wrapping_sub(S_BASE);
+    if s_index < S_COUNT {
+        let l = L_BASE + s_index / N_COUNT;
+        let v = V_BASE + (s_index % N_COUNT) / T_COUNT;
+        let t = T_BASE + s_index % T_COUNT;
+        out.extend([l, v].into_iter().filter_map(char::from_u32));
+        if t != T_BASE {
+            out.extend(char::from_u32(t));
+        }
+        return;
+    }
+    match TABLES.decompositions.get(&ch) {
+        Some(decomposition) => {
+            for &c in decomposition {
+                decompose_into(c, out);
+            }
+        }
+        None => out.push(ch),
+    }
+}
+
+/// NFD：完全分解后，连续的非起始字按组合类稳定排序
+fn decompose(text: &str) -> Vec<char> {
+    let mut chars = Vec::with_capacity(text.len());
+    for ch in text.chars() {
+        decompose_into(ch, &mut chars);
+    }
+    let mut i = 0;
+    while i < chars.len() {
+        if combining_class(chars[i]) == 0 {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && combining_class(chars[i]) != 0 {
+            i += 1;
+        }
+        chars[start..i].sort_by_key(|&ch| combining_class(ch));
+    }
+    chars
+}
+
+fn compose_pair(first: char, second: char) -> Option<char> {
+    let (first_code, second_code) = (first as u32, second as u32);
+    let l_index = first_code.wrapping_sub(L_BASE);
+    let v_index = second_code.wrapping_sub(V_BASE);
+    if l_index < L_COUNT && v_index < V_COUNT {
+        return char::from_u32(S_BASE + (l_index * V_COUNT + v_index) * T_COUNT);
+    }
+    let s_index = first_code.wrapping_sub(S_BASE);
+    let t_index = second_code.wrapping_sub(T_BASE);
+    if s_index < S_COUNT && s_index % T_COUNT == 0 && 0 < t_index && t_index < T_COUNT {
+        return char::from_u32(first_code + t_index);
+    }
+    TABLES.compositions.get(&(first, second)).copied()
+}
+
+/// Unicode NFC
+fn nfc(text: &str) -> String {
+    let mut out: Vec<char> = Vec::with_capacity(text.len());
+    // 最近的起始字在 `out` 中的位置，以及它之后最后一个字的组合类
+    let mut starter = None;
+    let mut last_class = None;
+    for ch in decompose(text) {
+        let class = combining_class(ch);
+        let blocked = last_class.is_some_and(|last| last == 0 || last >= class);
+        if let Some(i) = starter
+            && !blocked
+            && let Some(composed) = compose_pair(out[i], ch)
+        {
+            out[i] = composed;
+            continue;
+        }
+        if class == 0 {
+            starter = Some(out.len());
+            last_class = None;
+        } else {
+            last_class = Some(class);
+        }
+        out.push(ch);
+    }
+    out.into_iter().collect()
+}
+
+/// 全角字符、中文标点换成半角
+fn fold_width(ch: char) -> Option<&'static str> {
+    PUNCTUATION_FOLDS
+        .iter()
+        .find(|&&(from, _)| from == ch)
+        .map(|&(_, to)| to)
+}
+
+/// 按 v3 的规则规范化心印，`strip_punctuation` 为 `true` 时去掉全部标点符号和空白
+pub fn normalize_heartprint(text: &str, strip_punctuation: bool) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for ch in nfc(text).chars() {
+        match ch {
+            '\u{FF01}'..='\u{FF5E}' => folded.extend(char::from_u32(ch as u32 - 0xFEE0)),
+            _ => match fold_width(ch) {
+                Some(to) => folded.push_str(to),
+                None => folded.push(ch),
+            },
+        }
+    }
+
+    if strip_punctuation {
+        return folded
+            .chars()
+            .filter(|&ch| !ch.is_whitespace() && !is_punctuation(ch))
+            .collect();
+    }
+    folded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// 与 `text` 规范化后相同的几种常见写法：说明和写法，去掉了重复的写法
+///
+/// 供界面提示用户：换一种输入法或设备输入心印时，这些写法都能还原同一个钱包。
+pub fn heartprint_variants(text: &str, strip_punctuation: bool) -> Vec<(&'static str, String)> {
+    let half_width = normalize_heartprint(text, false);
+    let full_width = half_width
+        .chars()
+        .map(|ch| match ch {
+            '.' => '。',
+            '!'..='~' if !ch.is_ascii_alphanumeric() => {
+                char::from_u32(ch as u32 + 0xFEE0).unwrap_or(ch)
+            }
+            _ => ch,
+        })
+        .collect::<String>();
+
+    let mut variants = vec![
+        ("半角标点", half_width.clone()),
+        ("中文标点", full_width),
+        (
+            "分解形式（NFD，如 macOS 的部分输入法）",
+            decompose(&half_width).into_iter().collect(),
+        ),
+        ("首尾带空格", format!(" {} ", half_width)),
+    ];
+    if strip_punctuation {
+        variants.push(("不带标点和空格", normalize_heartprint(text, true)));
+    }
+
+    let mut seen = Vec::new();
+    variants.retain(|(_, variant)| {
+        let unique = !seen.contains(variant);
+        seen.push(variant.clone());
+        unique
+    });
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nfc() {
+        // é 的分解形式，谚文字母，组合类需要重排的越南文
+        assert_eq!(nfc("Cafe\u{301}"), "Café");
+        assert_eq!(nfc("\u{1112}\u{1161}\u{11AB}"), "한");
+        assert_eq!(nfc("a\u{323}\u{302}"), "\u{1EAD}");
+        assert_eq!(nfc("a\u{302}\u{323}"), "\u{1EAD}");
+        // 排除组合的字和单字分解
+        assert_eq!(nfc("\u{212B}"), "\u{C5}");
+        assert_eq!(nfc("\u{0958}"), "\u{0915}\u{093C}");
+        assert_eq!(nfc("星辰大海"), "星辰大海");
+        assert_eq!(decompose("한").len(), 3);
+    }
+
+    #[test]
+    fn test_normalize_heartprint() {
+        let expected = "星辰大海,心自无疆.";
+        for input in [
+            "星辰大海，心自无疆。",
+            "星辰大海,心自无疆.",
+            " 星辰大海，心自无疆。\n",
+            "星辰大海，心自无疆｡",
+        ] {
+            assert_eq!(normalize_heartprint(input, false), expected, "`{}`", input);
+        }
+        assert_eq!(normalize_heartprint("a \u{3000} b", false), "a b");
+        assert_eq!(
+            normalize_heartprint("星辰大海，心自无疆。", true),
+            "星辰大海心自无疆"
+        );
+        assert_eq!(
+            normalize_heartprint("“心印”——不立文字……", true),
+            "心印不立文字"
+        );
+        // 字母、数字和符号不受影响
+        assert_eq!(normalize_heartprint("Ｗallet＃1 $5", true), "Wallet1$5");
+    }
+
+    #[test]
+    fn test_heartprint_variants() {
+        for strip_punctuation in [false, true] {
+            let text = "星辰大海，心自无疆。Café";
+            let normalized = normalize_heartprint(text, strip_punctuation);
+            let variants = heartprint_variants(text, strip_punctuation);
+            assert!(variants.len() >= 4);
+            for (_, variant) in variants {
+                assert_eq!(
+                    normalize_heartprint(&variant, strip_punctuation),
+                    normalized
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_v3() {
+        use crate::{
+            Scheme, SchemeVersion, generate_words32_with, import_words32, import_words32_with,
+        };
+
+        let sk = [47u8; 32];
+        let v3 = Scheme::new(SchemeVersion::V3, 6, 666);
+        let words = generate_words32_with("星辰大海，心自无疆。", &v3, Some(&sk)).unwrap();
+        assert!(words.starts_with('㊂'));
+
+        // 半角标点、首尾空白和分解形式都视为同一句心印，v2 则不然
+        for heartprint in ["星辰大海,心自无疆.", " 星辰大海，心自无疆｡\n"] {
+            assert_eq!(import_words32(&words, heartprint, 6, 666).unwrap(), sk);
+        }
+        let v2 = Scheme::new(SchemeVersion::V2, 6, 666);
+        let v2_words = generate_words32_with("Café", &v2, Some(&sk)).unwrap();
+        assert_ne!(
+            import_words32_with(&v2_words, "Cafe\u{301}", &v2).ok(),
+            Some(sk)
+        );
+        let v3 = Scheme::new(SchemeVersion::V3, 6, 666);
+        let v3_words = generate_words32_with("Café", &v3, Some(&sk)).unwrap();
+        assert_eq!(
+            import_words32_with(&v3_words, "Cafe\u{301}", &v3).unwrap(),
+            sk
+        );
+        // 心印本来就是规范形式时，与 v2 只差版本标记
+        assert!(v2_words.chars().skip(1).eq(v3_words.chars().skip(1)));
+
+        // 去掉标点后，不带标点的心印也能还原
+        let scheme = Scheme {
+            strip_punctuation: true,
+            ..v3.clone()
+        };
+        let words = generate_words32_with("星辰大海，心自无疆。", &scheme, Some(&sk)).unwrap();
+        assert_eq!(
+            import_words32_with(&words, "星辰大海 心自无疆", &scheme).unwrap(),
+            sk
+        );
+        let v2 = Scheme {
+            strip_punctuation: true,
+            ..v2
+        };
+        assert!(generate_words32_with("不立文字", &v2, Some(&sk)).is_err());
+    }
+}
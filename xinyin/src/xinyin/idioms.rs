@@ -0,0 +1,197 @@
+//! 成语编码：以四字成语代替单字作为助记字
+//!
+//! 几十个互不相关的字很难记住，成语各有意思，容易记忆和口述。心印成语表与心印字表一样，
+//! 由心印派生的种子从内置成语表的选取范围中选出 `radix` 条；每条成语表示 log2(radix) 位，
+//! 4096 条时 32 字节的密钥为 22 条成语。
+//!
+//! 最初设想把 32 字节的密钥编成 8–10 条成语，但那样每条成语要表示 26–32 位，需要几千万条以上的成语，
+//! 远多于实际存在的成语；内置成语表只有 8192 条，每条最多表示 12 位，所以 22 条是 256 位密钥能做到的最少条数。
+
+// 切分和提示只在 import 中使用
+#![cfg_attr(not(feature = "import"), allow(dead_code))]
+
+use crate::{Error, Result};
+
+use super::{MIN_RANGE_COUNT, RANGE_MARGIN};
+
+const IDIOMS: &str = include_str!("../../idioms.txt");
+
+/// 每条成语的字数
+pub(super) const IDIOM_LEN: usize = 4;
+
+/// 内置成语表：8192 条四字成语，都由通用规范汉字表中的字组成，按常用程度排列
+pub fn idiom_list() -> Vec<&'static str> {
+    IDIOMS
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// 成语表选取范围的最大起始位置，范围的规则与基础字集相同
+pub fn idiom_max_start() -> usize {
+    idiom_list().len().saturating_sub(RANGE_MARGIN)
+}
+
+/// 从第 `start` 条成语（从 1 开始）起选取 `count` 条
+pub(super) fn slice(start: usize, count: usize) -> Result<Vec<&'static str>> {
+    let idioms = idiom_list();
+    let len = idioms.len();
+    if !(1..=idiom_max_start()).contains(&start) || count <= MIN_RANGE_COUNT || start + count > len
+    {
+        return Err(Error::InvalidRange { start, count, len });
+    }
+    Ok(idioms[start - 1..start - 1 + count].to_vec())
+}
+
+/// 把去掉分隔符的助记字按四字一组切分为成语，无法辨认的成语写成 `????`
+pub(super) fn split(words: &str) -> Result<Vec<String>> {
+    let chars = words.chars().collect::<Vec<char>>();
+    if !chars.len().is_multiple_of(IDIOM_LEN) {
+        return Err(Error::WrongLength(format!(
+            "idioms must have {} characters each, but got {} characters in total",
+            IDIOM_LEN,
+            chars.len()
+        )));
+    }
+    Ok(chars
+        .chunks(IDIOM_LEN)
+        .map(|chunk| chunk.iter().collect())
+        .collect())
+}
+
+/// 在心印成语表中找出与 `idiom` 只差一个字的成语，作为“您是不是要输入”的候选
+pub(super) fn suggest(idiom: &str, table: &[&str]) -> Vec<String> {
+    table
+        .iter()
+        .filter(|candidate| {
+            candidate
+                .chars()
+                .zip(idiom.chars())
+                .filter(|(a, b)| a != b)
+                .count()
+                == 1
+        })
+        .map(|candidate| candidate.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idiom_list() {
+        let words = include_str!("../../8105.bin");
+        let idioms = idiom_list();
+        assert_eq!(idioms.len(), 8192);
+        let mut unique = idioms.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), idioms.len());
+        for idiom in &idioms {
+            assert_eq!(idiom.chars().count(), IDIOM_LEN, "`{}`", idiom);
+            assert!(idiom.chars().all(|ch| words.contains(ch)), "`{}`", idiom);
+        }
+
+        assert_eq!(idiom_max_start(), 7687);
+        assert_eq!(slice(1, 8191).unwrap().len(), 8191);
+        assert!(slice(1, 8192).is_err());
+        assert!(slice(7688, 501).is_err());
+    }
+
+    #[test]
+    fn test_split_and_suggest() {
+        assert_eq!(
+            split("大吃一惊????").unwrap(),
+            vec!["大吃一惊".to_string(), "????".to_string()]
+        );
+        assert!(split("大吃一惊四面").is_err());
+
+        let table = ["大吃一惊", "四面八方", "四通八达"];
+        assert_eq!(suggest("大吃一京", &table), vec!["大吃一惊"]);
+        assert_eq!(suggest("四面八达", &table), vec!["四面八方", "四通八达"]);
+        assert!(suggest("????", &table).is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_idioms() {
+        use crate::{
+            Error, RecoveryCard, Scheme, SchemeVersion, generate_recovery_card,
+            generate_words32_with, import_recovery_card, import_words32_pinyin,
+            import_words32_repair, import_words32_with,
+        };
+
+        let sk = [43u8; 32];
+        let scheme = Scheme {
+            idioms: true,
+            radix: 4096,
+            checksum: 2,
+            ..Scheme::new(SchemeVersion::V2, 1, 8191)
+        };
+        let card = generate_recovery_card("不立文字", &scheme, Some(&sk)).unwrap();
+        let idioms = card.words['㊁'.len_utf8()..]
+            .split(' ')
+            .collect::<Vec<&str>>();
+        assert_eq!(idioms.len(), 23);
+        assert!(idioms.iter().all(|idiom| idiom_list().contains(idiom)));
+        for parsed in [
+            card.to_string().parse::<RecoveryCard>().unwrap(),
+            RecoveryCard::from_bytes(&card.to_bytes().unwrap()).unwrap(),
+        ] {
+            assert!(parsed.scheme.idioms);
+            assert_eq!(import_recovery_card(&parsed, "不立文字").unwrap(), sk);
+        }
+        // 不用空格分隔也可以还原
+        assert_eq!(
+            import_words32_with(&card.words.replace(' ', ""), "不立文字", &scheme).unwrap(),
+            sk
+        );
+
+        // 抄错一个字的成语列出只差一个字的候选
+        let mut typo = idioms
+            .iter()
+            .map(|idiom| idiom.to_string())
+            .collect::<Vec<String>>();
+        typo[5] = typo[5].chars().take(3).chain(['乂']).collect();
+        let err =
+            import_words32_with(&format!("㊁{}", typo.join(" ")), "不立文字", &scheme).unwrap_err();
+        let Error::UnknownIdioms(unknowns) = err else {
+            panic!("expected unknown idioms, got {:?}", err);
+        };
+        assert_eq!(unknowns[0].position, 5);
+        assert!(unknowns[0].suggestions.contains(&idioms[5].to_string()));
+
+        // 有纠错字时，无法辨认的成语写成 `????`
+        let scheme = Scheme {
+            parity: 9,
+            ..scheme
+        };
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+        let mut idioms = words['㊁'.len_utf8()..]
+            .split(' ')
+            .map(|idiom| idiom.to_string())
+            .collect::<Vec<String>>();
+        idioms[0] = "????".to_string();
+        idioms[10] = idioms[11].clone();
+        let damaged = format!("㊁{}", idioms.join("、"));
+        let (key, repaired) = import_words32_repair(&damaged, "不立文字", &scheme).unwrap();
+        assert_eq!(key, sk);
+        assert_eq!(repaired, vec![0, 10]);
+        assert!(import_words32_pinyin("da4 chi1", "不立文字", &scheme).is_err());
+
+        // 成语编码只能用于 v2，不能与选字选项同时使用
+        for scheme in [
+            Scheme {
+                idioms: true,
+                ..Scheme::v1(1, 8191)
+            },
+            Scheme {
+                confusable_free: true,
+                ..scheme.clone()
+            },
+        ] {
+            assert!(generate_words32_with("不立文字", &scheme, Some(&sk)).is_err());
+        }
+    }
+}
@@ -1,55 +1,268 @@
-use super::charset_256::generate_256_words;
-use anyhow::{Context, Result, bail};
+use super::card::RecoveryCard;
+use super::checksum::checksum;
+use super::confusables::suggest;
+use super::cts;
+use super::idioms;
+use super::normalize::normalize_words_in;
+use super::pinyin::pinyin_to_words;
+use super::radix;
+use super::reed_solomon;
+use super::scheme::Scheme;
+use crate::{Error, Result, UnknownCharacter, UnknownIdiom};
+use zeroize::Zeroizing;
 
-/// 导入心印密钥
+/// 导入心印密钥，方案版本由助记字中的版本标记自动识别
 pub fn import_words32(
     words32: &str,
     txt_in_heart: &str,
     start: usize,
     count: usize,
 ) -> Result<[u8; 32]> {
-    let words = generate_256_words(txt_in_heart, start, count)
-        .context("failed to generate Charset256 words")?;
-
-    let words32chars = words32.chars().collect::<Vec<char>>();
-    if words32chars.len() != 32 {
-        bail!(
-            "words32 must be exactly 32 characters, but got {}",
-            words32chars.len()
-        );
+    import_words32_with(words32, txt_in_heart, &Scheme::v1(start, count))
+}
+
+/// 按指定方案导入心印密钥
+///
+/// 方案版本以助记字中的版本标记为准，`scheme.version` 不参与导入。
+/// 助记字（不含版本标记）必须是 32 个字加上 `scheme.checksum` 个校验字和 `scheme.parity` 个纠错字，
+/// 字数不对时返回 [`Error::WrongLength`]，校验不通过时返回 [`Error::ChecksumMismatch`]。
+///
+/// 助记字中的分隔符（如 `、`、空格、换行）会被忽略，繁体字、异体字按规范字处理，
+/// 见 [`normalize_words`](crate::normalize_words)。
+pub fn import_words32_with(words32: &str, txt_in_heart: &str, scheme: &Scheme) -> Result<[u8; 32]> {
+    import_words32_repair(words32, txt_in_heart, scheme).map(|(key, _)| key)
+}
+
+/// 导入心印密钥，并检查它是否对应预期的钱包地址
+///
+/// 地址不符时返回 [`Error::AddressMismatch`]，说明心印或助记字有误。
+#[cfg(feature = "sks")]
+pub fn import_words32_verified(
+    words32: &str,
+    txt_in_heart: &str,
+    start: usize,
+    count: usize,
+    expected_address: &str,
+) -> Result<[u8; 32]> {
+    import_words32_verified_with(
+        words32,
+        txt_in_heart,
+        &Scheme::v1(start, count),
+        expected_address,
+    )
+}
+
+/// 按指定方案导入心印密钥，并检查它是否对应预期的钱包地址
+#[cfg(feature = "sks")]
+pub fn import_words32_verified_with(
+    words32: &str,
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    expected_address: &str,
+) -> Result<[u8; 32]> {
+    let key = import_words32_with(words32, txt_in_heart, scheme)?;
+    crate::sks::verify_address(&key, expected_address)?;
+    Ok(key)
+}
+
+/// 用拼音输入助记字并导入心印密钥，拼音的写法见 [`pinyin_to_words`]
+///
+/// 拼音对应多个字且无法确定时返回 [`Error::AmbiguousPinyin`]，需要让用户选择。
+pub fn import_words32_pinyin(
+    pinyin: &str,
+    txt_in_heart: &str,
+    scheme: &Scheme,
+) -> Result<[u8; 32]> {
+    let words = pinyin_to_words(pinyin, txt_in_heart, scheme)?;
+    import_words32_with(&words, txt_in_heart, scheme)
+}
+
+/// 用恢复卡和心印导入密钥，恢复卡中的密钥可以是任意长度
+pub fn import_recovery_card(card: &RecoveryCard, txt_in_heart: &str) -> Result<Vec<u8>> {
+    import_words(&card.words, txt_in_heart, &card.scheme)
+}
+
+/// 按指定方案导入心印密钥，同时返回被纠错字修正的字的位置
+///
+/// 方案带纠错字（`scheme.parity > 0`）时，无法辨认的字可以写成 `?`，不在心印字表中的字也按无法辨认处理。
+/// 返回的位置从 0 开始计数，不含版本标记字和分隔符。
+///
+/// 有字不在心印字表中且无法纠正时，返回 [`Error::UnknownCharacters`]，其中列出了每个字的形近字。
+///
+/// 成语编码（`scheme.idioms`）时，无法辨认的成语写成 `????`，返回的位置为成语的序号；
+/// 有成语不在心印成语表中且无法纠正时，返回 [`Error::UnknownIdioms`]。
+pub fn import_words32_repair(
+    words32: &str,
+    txt_in_heart: &str,
+    scheme: &Scheme,
+) -> Result<([u8; 32], Vec<usize>)> {
+    let (key, repaired) = decode(words32, txt_in_heart, scheme, Some(32))?;
+    let key = <[u8; 32]>::try_from(key.as_slice())
+        .map_err(|_| Error::WrongLength("secret key must be 32 bytes".to_string()))?;
+    Ok((key, repaired))
+}
+
+/// 按指定方案导入 [`generate_words`](crate::generate_words) 生成的任意长度的密钥
+///
+/// 密钥长度由字数确定：去掉版本标记、`scheme.checksum` 个校验字和 `scheme.parity` 个纠错字后，
+/// 剩下的每个字对应一个字节；心印字表不是 256 个字时，先按 `scheme.radix` 解包再按字节计算。
+pub fn import_words(words: &str, txt_in_heart: &str, scheme: &Scheme) -> Result<Vec<u8>> {
+    import_words_repair(words, txt_in_heart, scheme).map(|(secret, _)| secret)
+}
+
+/// 按指定方案导入任意长度的密钥，同时返回被纠错字修正的字的位置，见 [`import_words32_repair`]
+pub fn import_words_repair(
+    words: &str,
+    txt_in_heart: &str,
+    scheme: &Scheme,
+) -> Result<(Vec<u8>, Vec<usize>)> {
+    let (mut secret, repaired) = decode(words, txt_in_heart, scheme, None)?;
+    Ok((std::mem::take(&mut *secret), repaired))
+}
+
+/// 还原密钥，助记字先按方案的基础字集规范化，返回的位置都是规范化后的位置
+///
+/// 校验字个数取 `scheme.checksum`，密钥长度由字数确定；
+/// `secret_len` 不为 `None` 时密钥长度必须与之相同，否则返回 [`Error::WrongLength`]。
+/// 返回的密钥在释放时清零。
+pub(super) fn decode(
+    words: &str,
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    secret_len: Option<usize>,
+) -> Result<(Zeroizing<Vec<u8>>, Vec<usize>)> {
+    let normalized = normalize_words_in(words, &scheme.primary);
+    let (scheme, words) = scheme.detect(&normalized.words)?;
+    scheme.check_lengths(None)?;
+    let bits = scheme.radix_bits()?;
+
+    // 成语编码时每条成语是一个符号
+    let tokens = if scheme.idioms {
+        idioms::split(words)?
+    } else {
+        words.chars().map(String::from).collect()
+    };
+    let byte_len = radix::byte_len(tokens.len(), bits);
+    let checksum_len = scheme.checksum + scheme.parity;
+    let len = byte_len
+        .checked_sub(checksum_len)
+        .filter(|&len| len > 0)
+        .ok_or_else(|| {
+            Error::WrongLength(format!(
+                "words must have more than {} checksum and parity characters, but got {}",
+                checksum_len,
+                tokens.len()
+            ))
+        })?;
+    if let Some(secret_len) = secret_len.filter(|&secret_len| secret_len != len) {
+        return Err(Error::WrongLength(format!(
+            "words must be {} characters followed by {} checksum characters and {} parity characters, but got {}",
+            secret_len,
+            scheme.checksum,
+            scheme.parity,
+            tokens.len()
+        )));
     }
+    scheme.check_lengths(Some(len))?;
+    let secret_len = len;
 
-    let mut key = [0u8; 32];
-    for (i, word) in words32chars.iter().enumerate() {
-        let index = words.iter().position(|&w| w == *word).context(format!(
-            "failed to import xinyin words32: word `{}` not found in 256-words",
-            word
-        ))?;
-        key[i] = index as u8;
+    let derived = scheme.derive(txt_in_heart)?;
+    let charset = &derived.charset;
+
+    let mut symbols = Vec::with_capacity(tokens.len());
+    let mut unknowns = Unknowns::default();
+    for (i, token) in tokens.iter().enumerate() {
+        let index = if scheme.idioms {
+            let index = derived.idioms.iter().position(|idiom| idiom == token);
+            if index.is_none() {
+                unknowns.idioms.push(UnknownIdiom {
+                    position: i,
+                    idiom: token.clone(),
+                    suggestions: idioms::suggest(token, &derived.idioms),
+                });
+            }
+            index
+        } else {
+            let word = token.chars().next().unwrap_or_default();
+            let index = charset.iter().position(|&w| w == word);
+            if index.is_none() {
+                unknowns.chars.push(UnknownCharacter {
+                    position: i,
+                    ch: word,
+                    suggestions: suggest(word, charset),
+                });
+            }
+            index
+        };
+        symbols.push(index.unwrap_or(0));
     }
+    let mut bytes = Zeroizing::new(radix::unpack(&symbols, bits));
 
-    decrypt_xinyin_key(&mut key, txt_in_heart).context("failed to import xinyin words32")?;
+    let repaired = if scheme.parity > 0 {
+        // 纠错按字节进行，一个字的位可能落在两个字节中
+        let positions = unknowns.positions();
+        let mut erasures = positions
+            .iter()
+            .flat_map(|&position| radix::bytes_of_symbol(position, bits, byte_len))
+            .collect::<Vec<usize>>();
+        erasures.dedup();
+        match reed_solomon::correct(&mut bytes, scheme.parity, &erasures) {
+            // 重新打包，与输入不同的字就是被修正的字
+            Ok(_) => radix::pack(&bytes, bits)
+                .into_iter()
+                .zip(&symbols)
+                .enumerate()
+                .filter(|&(i, (corrected, &symbol))| corrected != symbol || positions.contains(&i))
+                .map(|(i, _)| i)
+                .collect(),
+            Err(_) if unknowns.has_suggestions() => return Err(unknowns.into_error()),
+            Err(err) => return Err(err),
+        }
+    } else if !unknowns.positions().is_empty() {
+        return Err(unknowns.into_error());
+    } else {
+        Vec::new()
+    };
 
-    Ok(key)
+    bytes.truncate(byte_len - scheme.parity);
+    let checksum_bytes = bytes.split_off(secret_len);
+    if checksum(&bytes, checksum_bytes.len()) != checksum_bytes {
+        return Err(Error::ChecksumMismatch);
+    }
+
+    cts::decrypt(&derived.aes_key, &derived.aes_iv, &mut bytes);
+
+    Ok((bytes, repaired))
 }
 
-use super::utils::generate_cbc_key_iv;
-use aes::{
-    Aes256,
-    cipher::{BlockModeDecrypt, KeyIvInit, block_padding::NoPadding},
-};
-use cbc::Decryptor;
-type Aes256CbcDec = Decryptor<Aes256>;
+/// 不在心印字表中的字，或不在心印成语表中的成语
+#[derive(Default)]
+struct Unknowns {
+    chars: Vec<UnknownCharacter>,
+    idioms: Vec<UnknownIdiom>,
+}
 
-fn decrypt_xinyin_key(encrypted_key: &mut [u8; 32], txt_in_heart: &str) -> Result<()> {
-    let (aes_key, aes_iv) =
-        generate_cbc_key_iv(txt_in_heart).context("failed to decrypt xinyin secret key")?;
+impl Unknowns {
+    fn positions(&self) -> Vec<usize> {
+        self.chars
+            .iter()
+            .map(|u| u.position)
+            .chain(self.idioms.iter().map(|u| u.position))
+            .collect()
+    }
 
-    Aes256CbcDec::new(&aes_key.into(), &aes_iv.into())
-        .decrypt_padded::<NoPadding>(encrypted_key)
-        .context("failed to decrypt xinyin secret key")?;
+    fn has_suggestions(&self) -> bool {
+        self.chars.iter().any(|u| !u.suggestions.is_empty())
+            || self.idioms.iter().any(|u| !u.suggestions.is_empty())
+    }
 
-    Ok(())
+    fn into_error(self) -> Error {
+        if self.idioms.is_empty() {
+            Error::UnknownCharacters(self.chars)
+        } else {
+            Error::UnknownIdioms(self.idioms)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -75,4 +288,37 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    #[cfg(feature = "sks")]
+    fn test_import_words32_verified() {
+        let words = "问抖廷亿丙驯弗付屹节尔力麦叮计寻右右问丑幻坏丙忆天闪父旬力扑抖延";
+        let address = "2gWUYMLPqVpqXnRdrF8h1s1p3y3C2xtrxQjyEzHgbEBG";
+        assert!(import_words32_verified(words, "不立文字", 6, 666, address).is_ok());
+
+        // 前两个字抄反了，都在字表中，只有核对地址才能发现
+        let swapped = "抖问廷亿丙驯弗付屹节尔力麦叮计寻右右问丑幻坏丙忆天闪父旬力扑抖延";
+        let err = import_words32_verified(swapped, "不立文字", 6, 666, address).unwrap_err();
+        assert!(matches!(err, Error::AddressMismatch { .. }));
+    }
+
+    #[test]
+    fn test_import_words32_unknown_characters() {
+        // 第 6 个字“驯”抄成了不在字表中的字，第 12 个字“力”抄成了形近的“刀”
+        let err = import_words32(
+            "问抖廷亿丙训弗付屹节尔刀麦叮计寻右右问丑幻坏丙忆天闪父旬力扑抖延",
+            "不立文字",
+            6,
+            666,
+        )
+        .unwrap_err();
+        let Error::UnknownCharacters(unknowns) = err else {
+            panic!("expected unknown characters, got {:?}", err);
+        };
+        assert_eq!(unknowns.len(), 2);
+        assert_eq!(unknowns[0].position, 5);
+        assert_eq!(unknowns[1].position, 11);
+        assert_eq!(unknowns[1].ch, '刀');
+        assert!(unknowns[1].suggestions.contains(&'力'));
+    }
 }
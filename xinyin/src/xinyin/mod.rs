@@ -1,8 +1,56 @@
+/// 选取范围之外至少保留的字数，起始位置最大为字集字数减去这个数
+pub(crate) const RANGE_MARGIN: usize = 505;
+
+/// 选取范围的最小字数（不含）
+pub const MIN_RANGE_COUNT: usize = 500;
+
+#[cfg(any(feature = "generate", feature = "import"))]
 mod charset_256;
+#[cfg(any(feature = "generate", feature = "import"))]
 mod utils;
 
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod card;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod charinfo;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod checksum;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod confusables;
+#[cfg(any(feature = "generate", feature = "import"))]
+mod cts;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod fingerprint;
+#[cfg(any(feature = "generate", feature = "import"))]
+mod gf256;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod heartprint;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod idioms;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod normalize;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod pinyin;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod primary;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod radix;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod reed_solomon;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod scheme;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod shamir;
+#[cfg(any(feature = "generate", feature = "import"))]
+pub mod strength;
+
+#[cfg(feature = "generate")]
+pub mod entropy;
 #[cfg(feature = "generate")]
 pub mod generate;
 
 #[cfg(feature = "import")]
 pub mod import;
+
+#[cfg(all(feature = "generate", feature = "import", feature = "sks"))]
+pub mod rekey;
@@ -0,0 +1,228 @@
+//! 助记字输入的规范化
+//!
+//! 抄在纸上、转述或从文档中复制的助记字常带有分隔符（`、`、`，`、空格、换行、表格边框），
+//! 或写成了繁体字、异体字、全角问号。导入前统一去掉分隔符，把字形换成《通用规范汉字表》中的字，
+//! 并记录换了哪些字，供界面提示用户核对。
+
+// 规范化只在 import 中使用，generate 只用到 `is_reserved`
+#![cfg_attr(not(feature = "import"), allow(dead_code))]
+
+use super::primary::PrimaryCharset;
+use super::scheme::MARKER_RANGE;
+
+const VARIANTS: &str = include_str!("../../variants.txt");
+
+/// 一处字形替换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    /// 在规范化后的助记字中的位置，从 0 开始，不含版本标记字
+    pub position: usize,
+    /// 输入的字
+    pub from: char,
+    /// 替换成的字
+    pub to: char,
+}
+
+/// 规范化后的助记字
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizedWords {
+    /// 规范化后的助记字，含版本标记字
+    pub words: String,
+    /// 去掉的分隔符个数
+    pub removed: usize,
+    /// 替换过字形的字
+    pub replaced: Vec<Replacement>,
+}
+
+impl NormalizedWords {
+    /// 输入是否被改动过
+    pub fn is_changed(&self) -> bool {
+        self.removed > 0 || !self.replaced.is_empty()
+    }
+}
+
+/// 繁体字、异体字或兼容字形对应的规范字，`ch` 本身是规范字或不在字表中时返回 `None`
+pub fn standard_form(ch: char) -> Option<char> {
+    if ch.is_ascii() {
+        return None;
+    }
+    VARIANTS
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let (standard, variants) = line.split_once(' ')?;
+            variants
+                .contains(ch)
+                .then(|| standard.chars().next())
+                .flatten()
+        })
+}
+
+/// 分隔符：空白、标点（`?` 除外）、表格边框
+fn is_separator(ch: char) -> bool {
+    ch.is_whitespace()
+        || (ch.is_ascii_punctuation() && ch != '?')
+        || matches!(
+            ch,
+            '\u{00B7}'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2500}'..='\u{259F}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{3014}'..='\u{301F}'
+                | '\u{30FB}'
+                | '\u{FE10}'..='\u{FE1F}'
+                | '\u{FE30}'..='\u{FE4F}'
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{FF1A}'..='\u{FF1E}'
+                | '\u{FF20}'
+                | '\u{FF3B}'..='\u{FF40}'
+                | '\u{FF5B}'..='\u{FF65}'
+        )
+}
+
+/// 在输入中有特殊含义、不能出现在基础字集里的字：ASCII、分隔符、全角字符和版本标记字
+pub(super) fn is_reserved(ch: char) -> bool {
+    ch.is_ascii() || is_separator(ch) || half_width(ch).is_some() || MARKER_RANGE.contains(&ch)
+}
+
+/// 全角字母、数字和问号对应的半角字符
+fn half_width(ch: char) -> Option<char> {
+    match ch {
+        '\u{FF10}'..='\u{FF19}'
+        | '\u{FF1F}'
+        | '\u{FF21}'..='\u{FF3A}'
+        | '\u{FF41}'..='\u{FF5A}' => char::from_u32(ch as u32 - 0xFEE0),
+        _ => None,
+    }
+}
+
+/// 按默认的基础字集（《通用规范汉字表》）规范化输入的助记字
+///
+/// 去掉分隔符，全角字符换成半角，繁体字、异体字换成规范字；其它字原样保留，由导入时报告为无法识别。
+pub fn normalize_words(input: &str) -> NormalizedWords {
+    normalize_words_in(input, &PrimaryCharset::default())
+}
+
+/// 按指定的基础字集规范化输入的助记字
+///
+/// 只有不在基础字集中、而对应的规范字在基础字集中的字才会被替换，繁体、日文字集中的字保持原样。
+pub fn normalize_words_in(input: &str, primary: &PrimaryCharset) -> NormalizedWords {
+    let primary = primary.chars().unwrap_or_default();
+    let standard_in_primary = |ch: char| {
+        standard_form(ch).filter(|standard| !primary.contains(&ch) && primary.contains(standard))
+    };
+
+    let mut normalized = NormalizedWords::default();
+    let mut position = 0;
+    for ch in input.chars() {
+        if is_separator(ch) {
+            normalized.removed += 1;
+            continue;
+        }
+
+        // 版本标记字只可能出现在最前面，不计入位置
+        if normalized.words.is_empty() && MARKER_RANGE.contains(&ch) {
+            normalized.words.push(ch);
+            continue;
+        }
+
+        match half_width(ch).or_else(|| standard_in_primary(ch)) {
+            Some(to) => {
+                normalized.replaced.push(Replacement {
+                    position,
+                    from: ch,
+                    to,
+                });
+                normalized.words.push(to);
+            }
+            None => normalized.words.push(ch),
+        }
+        position += 1;
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants_in_8105() {
+        let words = include_str!("../../8105.bin");
+        for line in VARIANTS.lines().filter(|line| !line.starts_with('#')) {
+            let (standard, variants) = line.split_once(' ').unwrap();
+            assert_eq!(standard.chars().count(), 1, "line `{}`", line);
+            assert!(
+                words.contains(standard),
+                "`{}` is not in 8105.bin",
+                standard
+            );
+            for ch in variants.chars() {
+                assert!(!words.contains(ch), "`{}` is in 8105.bin", ch);
+                assert_eq!(standard_form(ch), standard.chars().next());
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_words() {
+        let normalized = normalize_words(" ㊁潮、映，澄 後 髮\n│問│？│ ");
+        assert_eq!(normalized.words, "㊁潮映澄后发问?");
+        assert_eq!(normalized.removed, 10);
+        assert_eq!(
+            normalized.replaced,
+            vec![
+                Replacement {
+                    position: 3,
+                    from: '後',
+                    to: '后'
+                },
+                Replacement {
+                    position: 4,
+                    from: '髮',
+                    to: '发'
+                },
+                Replacement {
+                    position: 5,
+                    from: '問',
+                    to: '问'
+                },
+                Replacement {
+                    position: 6,
+                    from: '？',
+                    to: '?'
+                },
+            ]
+        );
+
+        let normalized = normalize_words("问抖");
+        assert_eq!(normalized.words, "问抖");
+        assert!(!normalized.is_changed());
+
+        // 繁体字集中的字不替换
+        let normalized = normalize_words_in("問、後", &PrimaryCharset::Big5Common);
+        assert_eq!(normalized.words, "問後");
+        assert!(normalized.replaced.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "import")]
+    fn test_import_words32_normalized() {
+        use crate::import_words32;
+
+        // 按 README 的写法用顿号分隔、分行抄写，部分字写成了繁体
+        let words = "問、抖、廷、亿、丙、驯、弗、付\n屹、節、爾、力、麥、叮、計、尋\n右、右、问、丑、幻、坏、丙、忆\n天、閃、父、旬、力、扑、抖、延。";
+        let secret_key = import_words32(words, "不立文字", 6, 666).unwrap();
+        assert_eq!(
+            secret_key,
+            import_words32(
+                "问抖廷亿丙驯弗付屹节尔力麦叮计寻右右问丑幻坏丙忆天闪父旬力扑抖延",
+                "不立文字",
+                6,
+                666
+            )
+            .unwrap()
+        );
+    }
+}
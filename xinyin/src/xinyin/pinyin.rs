@@ -0,0 +1,480 @@
+//! 用拼音输入助记字
+//!
+//! 心印字表只有 256 个字，一个带声调的音节在字表中通常只对应一两个字；
+//! 对应多个字时先用校验字排除，仍然无法确定的交给用户选择。
+
+use crate::{Error, PinyinSyllable, Result};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use super::checksum::{MAX_CHECKSUM_WORDS, checksum};
+use super::radix;
+use super::scheme::{Scheme, SchemeVersion};
+
+const PINYIN: &str = include_str!("../../pinyin.txt");
+
+/// 用校验字排除同音字时最多尝试的组合数
+const MAX_COMBINATIONS: usize = 4096;
+
+/// 拼音表：每个字的全部带调拼音，最常用的读音在前
+static TABLE: LazyLock<HashMap<char, Vec<&'static str>>> = LazyLock::new(|| {
+    PINYIN
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let ch = fields.next()?.chars().next()?;
+            Some((ch, fields.collect()))
+        })
+        .collect()
+});
+
+/// `ch` 的带调拼音，最常用的读音在前，如 `行` 为 `["xing2", "hang2", ...]`
+///
+/// 声调用数字 1-4 表示，5 为轻声，`v` 表示 ü；表中没有收录时返回空。
+pub fn pinyin_of(ch: char) -> Vec<&'static str> {
+    TABLE.get(&ch).cloned().unwrap_or_default()
+}
+
+/// 每个字的全部带调拼音，顺序同 [`pinyin_of`]
+pub(super) fn pinyin_table() -> &'static HashMap<char, Vec<&'static str>> {
+    &TABLE
+}
+
+/// 把带调拼音分为音节和声调，如 `wen4` 为 `("wen", Some(4))`；没有声调数字时声调为 `None`
+pub(super) fn split_tone(pinyin: &str) -> (&str, Option<u8>) {
+    match pinyin.chars().last().and_then(|ch| ch.to_digit(10)) {
+        Some(tone) => (&pinyin[..pinyin.len() - 1], Some(tone as u8)),
+        None => (pinyin, None),
+    }
+}
+
+/// 去掉声调后的音节是否在拼音表中
+fn is_syllable(syllable: &str) -> bool {
+    TABLE
+        .values()
+        .flatten()
+        .any(|pinyin| split_tone(pinyin).0 == syllable)
+}
+
+/// 把带声调符号的韵母换成不带声调的字母和声调
+fn strip_tone_mark(ch: char) -> Option<(char, Option<u8>)> {
+    const MARKS: [(char, [char; 4]); 6] = [
+        ('a', ['ā', 'á', 'ǎ', 'à']),
+        ('e', ['ē', 'é', 'ě', 'è']),
+        ('i', ['ī', 'í', 'ǐ', 'ì']),
+        ('o', ['ō', 'ó', 'ǒ', 'ò']),
+        ('u', ['ū', 'ú', 'ǔ', 'ù']),
+        ('v', ['ǖ', 'ǘ', 'ǚ', 'ǜ']),
+    ];
+
+    let ch = ch.to_lowercase().next()?;
+    match ch {
+        'a'..='z' => Some((ch, None)),
+        'ü' => Some(('v', None)),
+        _ => MARKS.iter().find_map(|(base, marked)| {
+            let tone = marked.iter().position(|&m| m == ch)?;
+            Some((*base, Some(tone as u8 + 1)))
+        }),
+    }
+}
+
+/// 输入中的一个字或音节
+enum Token {
+    Char(char),
+    Syllable {
+        text: String,
+        syllable: String,
+        tone: Option<u8>,
+    },
+}
+
+/// 把连续的拼音字母按最长匹配切分为音节，`tone` 为紧跟其后的数字声调
+fn split_syllables(run: &[char], tone: Option<u8>, tokens: &mut Vec<Token>) {
+    let mut start = 0;
+    while start < run.len() {
+        let letters = run[start..]
+            .iter()
+            .map(|&c| strip_tone_mark(c).unwrap_or((c, None)))
+            .collect::<Vec<_>>();
+        let len = (1..=letters.len().min(6))
+            .rev()
+            .find(|&len| {
+                let syllable = letters[..len].iter().map(|(c, _)| c).collect::<String>();
+                is_syllable(&syllable)
+            })
+            .unwrap_or(letters.len());
+
+        let end = start + len;
+        let mark = letters[..len].iter().find_map(|(_, tone)| *tone);
+        tokens.push(Token::Syllable {
+            text: run[start..end].iter().collect(),
+            syllable: letters[..len].iter().map(|(c, _)| c).collect(),
+            tone: if end == run.len() {
+                tone.or(mark)
+            } else {
+                mark
+            },
+        });
+        start = end;
+    }
+}
+
+/// 把输入切分为字和音节
+///
+/// 音节之间可以用空格、逗号、撇号或声调数字分隔，不分隔时按最长匹配切分。
+/// 汉字和 `?` 原样保留。
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut run: Vec<char> = Vec::new();
+    for ch in input.chars() {
+        if strip_tone_mark(ch).is_some() || (ch == ':' && run.last() == Some(&'u')) {
+            if ch == ':' {
+                run.pop();
+                run.push('ü');
+            } else {
+                run.push(ch);
+            }
+            continue;
+        }
+
+        let tone = ch.to_digit(10).map(|d| if d == 0 { 5 } else { d as u8 });
+        split_syllables(&run, tone.filter(|&t| t <= 5), &mut tokens);
+        run.clear();
+
+        if tone.is_none() && !ch.is_whitespace() && !",，'’-、".contains(ch) {
+            tokens.push(Token::Char(ch));
+        }
+    }
+    split_syllables(&run, None, &mut tokens);
+    tokens
+}
+
+/// 心印字表中读 `syllable` 的字，按字表中的顺序；标了声调但没有同调的字时，按不带声调查找
+fn candidates(syllable: &str, tone: Option<u8>, charset: &[char]) -> Vec<char> {
+    let matches = |with_tone: bool| {
+        charset
+            .iter()
+            .copied()
+            .filter(|ch| {
+                TABLE.get(ch).into_iter().flatten().any(|pinyin| {
+                    let (base, pinyin_tone) = split_tone(pinyin);
+                    base == syllable && (!with_tone || tone.is_none() || pinyin_tone == tone)
+                })
+            })
+            .collect::<Vec<char>>()
+    };
+
+    let found = matches(true);
+    if found.is_empty() && tone.is_some() {
+        matches(false)
+    } else {
+        found
+    }
+}
+
+/// 用校验字排除同音字，只有一种组合通过校验时返回该组合
+fn resolve_by_checksum(
+    slots: &[Vec<char>],
+    charset: &[char],
+    scheme: &Scheme,
+) -> Option<Vec<char>> {
+    let bits = scheme.radix_bits().ok()?;
+    let byte_len = radix::byte_len(slots.len(), bits);
+    let data_len = byte_len.checked_sub(scheme.parity)?;
+    let checksum_len = if scheme.checksum > 0 {
+        scheme.checksum
+    } else {
+        data_len
+            .checked_sub(32)
+            .filter(|&len| len <= MAX_CHECKSUM_WORDS)?
+    };
+    if checksum_len == 0 || checksum_len > data_len {
+        return None;
+    }
+
+    let combinations = slots.iter().try_fold(1usize, |n, slot| {
+        n.checked_mul(slot.len()).filter(|&n| n <= MAX_COMBINATIONS)
+    })?;
+
+    let mut resolved = None;
+    for mut n in 0..combinations {
+        let chars = slots
+            .iter()
+            .map(|slot| {
+                let ch = slot[n % slot.len()];
+                n /= slot.len();
+                ch
+            })
+            .collect::<Vec<char>>();
+        // 纠错字中可以有无法辨认的字，密钥和校验字中不行
+        let mut symbols = Vec::with_capacity(chars.len());
+        for (i, c) in chars.iter().enumerate() {
+            match charset.iter().position(|w| w == c) {
+                Some(symbol) => symbols.push(symbol),
+                None if radix::bytes_of_symbol(i, bits, byte_len).start >= data_len => {
+                    symbols.push(0)
+                }
+                None => return None,
+            }
+        }
+        let bytes = radix::unpack(&symbols, bits);
+        let (encrypted, sum) = bytes[..data_len].split_at(data_len - checksum_len);
+        if checksum(encrypted, checksum_len) == sum {
+            if resolved.is_some() {
+                return None;
+            }
+            resolved = Some(chars);
+        }
+    }
+    resolved
+}
+
+/// 把拼音输入的助记字换成心印字表中的字
+///
+/// 每个音节可以带数字声调（`wen4`）、声调符号（`wèn`）或不带声调（`wen`），ü 可以写成 `v` 或 `u:`。
+/// 输入中可以夹杂汉字和 `?`（无法辨认的字），原样保留。
+/// 输入以版本标记字开头时按标记确定方案版本，否则使用 `scheme.version`。
+///
+/// 音节在字表中找不到时返回 [`Error::UnknownPinyin`]；
+/// 对应多个字且校验字无法确定时返回 [`Error::AmbiguousPinyin`]，列出每个位置的候选字，
+/// 可以把选定的字替换进输入后重新转换，或者用 [`pinyin_to_words_with`] 逐个选择。
+/// 成语编码（`scheme.idioms`）不支持拼音输入。
+pub fn pinyin_to_words(pinyin: &str, txt_in_heart: &str, scheme: &Scheme) -> Result<String> {
+    pinyin_to_words_with(pinyin, txt_in_heart, scheme, |_| None)
+}
+
+/// 同 [`pinyin_to_words`]，校验字无法确定的同音字由 `choose` 从候选字中选出
+///
+/// `choose` 返回 `None` 或不在候选字中的字时，返回 [`Error::AmbiguousPinyin`]。
+pub fn pinyin_to_words_with(
+    pinyin: &str,
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    mut choose: impl FnMut(&PinyinSyllable) -> Option<char>,
+) -> Result<String> {
+    let pinyin = pinyin.trim();
+    let (version, rest) = SchemeVersion::detect(pinyin)?;
+    let scheme = Scheme {
+        version: if rest.len() < pinyin.len() {
+            version
+        } else {
+            scheme.version
+        },
+        ..scheme.clone()
+    };
+    if scheme.idioms {
+        return Err(Error::InvalidParams(
+            "pinyin input is not available for idiom encoding".to_string(),
+        ));
+    }
+    scheme.check_lengths(None)?;
+    let charset = scheme.derive(txt_in_heart)?.charset;
+
+    let mut slots = Vec::new();
+    let mut unknowns = Vec::new();
+    let mut ambiguous = Vec::new();
+    for (position, token) in tokenize(rest).into_iter().enumerate() {
+        match token {
+            Token::Char(ch) => slots.push(vec![ch]),
+            Token::Syllable {
+                text,
+                syllable,
+                tone,
+            } => {
+                let found = candidates(&syllable, tone, &charset);
+                let entry = PinyinSyllable {
+                    position,
+                    syllable: text,
+                    candidates: found.clone(),
+                };
+                match found.len() {
+                    0 => unknowns.push(entry),
+                    1 => {}
+                    _ => ambiguous.push(entry),
+                }
+                slots.push(found);
+            }
+        }
+    }
+
+    if !unknowns.is_empty() {
+        return Err(Error::UnknownPinyin(unknowns));
+    }
+
+    let chars = if ambiguous.is_empty() {
+        slots.into_iter().map(|slot| slot[0]).collect()
+    } else if let Some(chars) = resolve_by_checksum(&slots, &charset, &scheme) {
+        chars
+    } else {
+        for syllable in &ambiguous {
+            match choose(syllable).filter(|ch| syllable.candidates.contains(ch)) {
+                Some(ch) => slots[syllable.position] = vec![ch],
+                None => return Err(Error::AmbiguousPinyin(ambiguous)),
+            }
+        }
+        slots.into_iter().map(|slot| slot[0]).collect()
+    };
+
+    let mut words = scheme
+        .version
+        .marker()
+        .map(String::from)
+        .unwrap_or_default();
+    words.extend(chars);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinyin_of() {
+        assert_eq!(pinyin_of('问'), vec!["wen4"]);
+        assert_eq!(
+            pinyin_of('行'),
+            vec!["xing2", "hang2", "heng2", "xing4", "hang4"]
+        );
+        assert_eq!(pinyin_of('略'), vec!["lve4"]);
+        // 容易读错声调的字
+        for (ch, pinyin) in [
+            ('癌', "ai2"),
+            ('皑', "ai2"),
+            ('矮', "ai3"),
+            ('蔼', "ai3"),
+            ('巴', "ba1"),
+            ('罢', "ba4"),
+            ('蟆', "ma2"),
+            ('狸', "li2"),
+            ('犸', "ma3"),
+            ('驯', "xun4"),
+            ('吧', "ba5"),
+        ] {
+            assert_eq!(pinyin_of(ch)[0], pinyin, "`{}`", ch);
+        }
+
+        let words = include_str!("../../8105.bin");
+        assert_eq!(TABLE.len(), words.chars().count());
+        for (ch, pinyins) in TABLE.iter() {
+            assert!(words.contains(*ch), "`{}` is not in 8105.bin", ch);
+            assert!(!pinyins.is_empty(), "`{}` has no pinyin", ch);
+            for pinyin in pinyins {
+                assert!(
+                    matches!(split_tone(pinyin), (base, Some(1..=5)) if !base.is_empty()),
+                    "`{}` has no tone",
+                    pinyin
+                );
+            }
+        }
+        assert_eq!(split_tone("lve"), ("lve", None));
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let syllables = |input: &str| {
+            tokenize(input)
+                .into_iter()
+                .map(|token| match token {
+                    Token::Char(ch) => ch.to_string(),
+                    Token::Syllable { syllable, tone, .. } => {
+                        format!(
+                            "{}{}",
+                            syllable,
+                            tone.map_or(String::new(), |t| t.to_string())
+                        )
+                    }
+                })
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(syllables("wen4 dou3,ting2"), ["wen4", "dou3", "ting2"]);
+        assert_eq!(syllables("wen4dou3ting2"), ["wen4", "dou3", "ting2"]);
+        assert_eq!(syllables("wèndǒu tíng"), ["wen4", "dou3", "ting2"]);
+        assert_eq!(syllables("lü4 lv lu:e4"), ["lv4", "lv", "lve4"]);
+        assert_eq!(syllables("wen ? 抖 xi'an"), ["wen", "?", "抖", "xi", "an"]);
+    }
+
+    #[test]
+    fn test_pinyin_to_words() {
+        let words = "问抖廷亿丙驯弗付屹节尔力麦叮计寻右右问丑幻坏丙忆天闪父旬力扑抖延";
+        let pinyin = "wen4 dou3 ting2 yi4 bing3 xun4 fu2 fu4 yi4 jie2 er3 li4 mai4 ding1 ji4 xun2 \
+                      you4 you4 wen4 chou3 huan4 huai4 bing3 yi4 tian1 shan3 fu4 xun2 li4 pu1 dou3 yan2";
+        let scheme = Scheme::v1(6, 666);
+
+        // v1 没有校验字，字表中的同音字无法自动区分，多音字的每个读音都算在内，如 `印` 也读 yi4
+        let err = pinyin_to_words(pinyin, "不立文字", &scheme).unwrap_err();
+        let Error::AmbiguousPinyin(ambiguous) = err else {
+            panic!("unexpected error: {}", err);
+        };
+        let ambiguous = ambiguous
+            .iter()
+            .map(|s| (s.position, s.candidates.iter().collect::<String>()))
+            .collect::<Vec<(usize, String)>>();
+        let expected = [
+            (1, "斗抖"),
+            (3, "印亿失艺夕艾忆屹"),
+            (6, "市芙伏扶弗包"),
+            (7, "伏父负付"),
+            (8, "印亿失艺夕艾忆屹"),
+            (11, "力立"),
+            (14, "计记纪"),
+            (15, "旬寻驯"),
+            (16, "叹有右"),
+            (17, "叹有右"),
+            (19, "丑扭"),
+            (23, "印亿失艺夕艾忆屹"),
+            (26, "伏父负付"),
+            (27, "旬寻驯"),
+            (28, "力立"),
+            (30, "斗抖"),
+        ]
+        .map(|(position, candidates)| (position, candidates.to_string()));
+        assert_eq!(ambiguous, expected);
+
+        let resolved = pinyin_to_words_with(pinyin, "不立文字", &scheme, |syllable| {
+            words.chars().nth(syllable.position)
+        })
+        .unwrap();
+        assert_eq!(resolved, words);
+
+        let err = pinyin_to_words("wen4 zzz", "不立文字", &scheme).unwrap_err();
+        assert!(matches!(err, Error::UnknownPinyin(ref u) if u[0].position == 1));
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_pinyin() {
+        use crate::{Scheme, SchemeVersion, generate_words32_with, import_words32_pinyin};
+
+        let sk = [17u8; 32];
+        let scheme = Scheme {
+            checksum: 2,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+
+        // 不带版本标记，带声调时同音字很少，由校验字排除
+        let pinyin = words
+            .chars()
+            .skip(1)
+            .map(|ch| pinyin_of(ch)[0])
+            .collect::<Vec<&str>>()
+            .join(" ");
+        assert_eq!(
+            import_words32_pinyin(&pinyin, "不立文字", &scheme).unwrap(),
+            sk
+        );
+    }
+
+    #[test]
+    fn test_pinyin_to_words_rejects_out_of_range_checksum() {
+        let scheme = Scheme {
+            checksum: 40,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        let pinyin = vec!["wen4"; 80].join(" ");
+        let err = pinyin_to_words(&pinyin, "x", &scheme).unwrap_err();
+        assert_eq!(err.code(), "invalid_params");
+    }
+}
@@ -0,0 +1,332 @@
+//! 基础字集：心印字表从中选出 256 个字
+//!
+//! 默认是《通用规范汉字表》的 8105 字，也可以换成其它地区常用的字集，或者用户自备的字集文件。
+//! 基础字集不写入助记字，需要和助记字一起保存；恢复卡中记录了字集的名称，自备字集只记录 SHA256。
+
+use crate::{Error, Result};
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+
+use super::normalize::is_reserved;
+use super::{MIN_RANGE_COUNT, RANGE_MARGIN};
+
+const TONGYONG_8105: &str = include_str!("../../8105.bin");
+const GB2312_LEVEL1: &str = include_str!("../../gb2312-1.txt");
+const BIG5_COMMON: &str = include_str!("../../big5-common.txt");
+const JIS_LEVEL1: &str = include_str!("../../jis-1.txt");
+
+/// 谚文音节：가(U+AC00) ..= 힣(U+D7A3)
+const HANGUL_SYLLABLES: std::ops::RangeInclusive<char> = '\u{AC00}'..='\u{D7A3}';
+
+/// 用户自备的基础字集
+///
+/// 字集文件中每个字只能出现一次，空白和以 `#` 开头的行会被忽略，字的先后顺序有意义。
+/// 恢复卡中只记录字集的 SHA256，还原时需要提供同一个文件，用 [`CustomCharset::load`] 核对后载入。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomCharset {
+    /// 字集中的字，只知道 SHA256、尚未载入时为空
+    chars: Vec<char>,
+    sha256: [u8; 32],
+}
+
+impl CustomCharset {
+    /// 解析字集文件的内容
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut chars = Vec::new();
+        for line in text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+        {
+            for ch in line.chars().filter(|ch| !ch.is_whitespace()) {
+                if is_reserved(ch) {
+                    return Err(Error::InvalidParams(format!(
+                        "`{}` can not be used in a custom charset",
+                        ch
+                    )));
+                }
+                if chars.contains(&ch) {
+                    return Err(Error::InvalidParams(format!(
+                        "`{}` appears more than once in the custom charset",
+                        ch
+                    )));
+                }
+                chars.push(ch);
+            }
+        }
+        if chars.len() <= RANGE_MARGIN {
+            return Err(Error::InvalidParams(format!(
+                "custom charset must have more than {} characters, but got {}",
+                RANGE_MARGIN,
+                chars.len()
+            )));
+        }
+
+        let sha256 = Sha256::digest(chars.iter().collect::<String>().as_bytes()).into();
+        Ok(Self { chars, sha256 })
+    }
+
+    /// 只有 SHA256 的字集，还原前需要 [`CustomCharset::load`]
+    pub fn from_sha256(sha256: [u8; 32]) -> Self {
+        Self {
+            chars: Vec::new(),
+            sha256,
+        }
+    }
+
+    /// 载入字集文件，文件内容与记录的 SHA256 不符时返回 [`Error::CharsetMismatch`]
+    pub fn load(&self, text: &str) -> Result<Self> {
+        let loaded = Self::parse(text)?;
+        if loaded.sha256 != self.sha256 {
+            return Err(Error::CharsetMismatch {
+                expected: hex(&self.sha256),
+                actual: hex(&loaded.sha256),
+            });
+        }
+        Ok(loaded)
+    }
+
+    pub fn sha256(&self) -> [u8; 32] {
+        self.sha256
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        !self.chars.is_empty()
+    }
+}
+
+/// 内置字集文件中的字：除注释行外，各行的字按顺序连接
+fn builtin_chars(file: &str) -> Vec<char> {
+    file.lines()
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(|line| line.trim().chars())
+        .collect()
+}
+
+/// 基础字集
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PrimaryCharset {
+    /// 《通用规范汉字表》8105 字，按一级、二级、三级字表排列
+    #[default]
+    Tongyong8105,
+    /// GB 2312 一级汉字 3755 字，按拼音排列
+    Gb2312Level1,
+    /// Big5 常用字 5401 字（繁体），按笔画排列
+    Big5Common,
+    /// JIS X 0208 第一水准汉字 2965 字（日文），按音读排列，涵盖了绝大部分常用汉字
+    JisLevel1,
+    /// 现代朝鲜语的全部 11172 个谚文音节，按字母顺序排列
+    Hangul,
+    /// 用户自备的字集
+    Custom(CustomCharset),
+}
+
+impl PrimaryCharset {
+    /// 内置的字集
+    pub const BUILTIN: &'static [PrimaryCharset] = &[
+        PrimaryCharset::Tongyong8105,
+        PrimaryCharset::Gb2312Level1,
+        PrimaryCharset::Big5Common,
+        PrimaryCharset::JisLevel1,
+        PrimaryCharset::Hangul,
+    ];
+
+    /// 简短的说明，供界面显示
+    pub fn description(&self) -> &'static str {
+        match self {
+            PrimaryCharset::Tongyong8105 => "通用规范汉字表（8105 字）",
+            PrimaryCharset::Gb2312Level1 => "GB 2312 一级汉字（3755 字）",
+            PrimaryCharset::Big5Common => "Big5 常用字（5401 字，繁体）",
+            PrimaryCharset::JisLevel1 => "JIS 第一水准汉字（2965 字，日文）",
+            PrimaryCharset::Hangul => "谚文音节（11172 字，朝鲜文）",
+            PrimaryCharset::Custom(_) => "自备字集",
+        }
+    }
+
+    /// 字集中的全部字，自备字集尚未载入时返回错误
+    pub fn chars(&self) -> Result<Vec<char>> {
+        Ok(match self {
+            PrimaryCharset::Tongyong8105 => TONGYONG_8105.chars().collect(),
+            PrimaryCharset::Gb2312Level1 => builtin_chars(GB2312_LEVEL1),
+            PrimaryCharset::Big5Common => builtin_chars(BIG5_COMMON),
+            PrimaryCharset::JisLevel1 => builtin_chars(JIS_LEVEL1),
+            PrimaryCharset::Hangul => HANGUL_SYLLABLES.collect(),
+            PrimaryCharset::Custom(custom) => {
+                if !custom.is_loaded() {
+                    return Err(Error::InvalidParams(format!(
+                        "custom charset {} is not loaded",
+                        hex(&custom.sha256)
+                    )));
+                }
+                custom.chars.clone()
+            }
+        })
+    }
+
+    /// 字集的字数，自备字集尚未载入时为 0
+    pub fn char_count(&self) -> usize {
+        self.chars().map(|chars| chars.len()).unwrap_or(0)
+    }
+
+    /// 允许的选取范围：起始位置（从 1 开始）不超过 `max_start`，`start + count` 不超过字数
+    pub fn max_start(&self) -> usize {
+        self.char_count().saturating_sub(RANGE_MARGIN)
+    }
+
+    /// 从第 `start` 个字（从 1 开始）起选取 `count` 个字
+    pub(super) fn slice(&self, start: usize, count: usize) -> Result<Vec<char>> {
+        let chars = self.chars()?;
+        let len = chars.len();
+        if !(1..=len.saturating_sub(RANGE_MARGIN)).contains(&start)
+            || count <= MIN_RANGE_COUNT
+            || start + count > len
+        {
+            return Err(Error::InvalidRange { start, count, len });
+        }
+        Ok(chars[start - 1..start - 1 + count].to_vec())
+    }
+}
+
+/// 文本形式：内置字集为 `8105`、`gb2312-1`、`big5`、`jis-1`、`hangul`，自备字集为 `custom:` 加 SHA256
+impl fmt::Display for PrimaryCharset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimaryCharset::Tongyong8105 => write!(f, "8105"),
+            PrimaryCharset::Gb2312Level1 => write!(f, "gb2312-1"),
+            PrimaryCharset::Big5Common => write!(f, "big5"),
+            PrimaryCharset::JisLevel1 => write!(f, "jis-1"),
+            PrimaryCharset::Hangul => write!(f, "hangul"),
+            PrimaryCharset::Custom(custom) => write!(f, "custom:{}", hex(&custom.sha256)),
+        }
+    }
+}
+
+/// 自备字集解析后尚未载入
+impl FromStr for PrimaryCharset {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(sha256) = s.strip_prefix("custom:") {
+            let sha256 = (sha256.len() == 64 && sha256.is_ascii())
+                .then(|| {
+                    (0..32)
+                        .map(|i| u8::from_str_radix(&sha256[i * 2..i * 2 + 2], 16).ok())
+                        .collect::<Option<Vec<u8>>>()
+                })
+                .flatten()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                .ok_or_else(|| {
+                    Error::InvalidFormat(format!("invalid custom charset sha256 `{}`", sha256))
+                })?;
+            return Ok(PrimaryCharset::Custom(CustomCharset::from_sha256(sha256)));
+        }
+
+        Self::BUILTIN
+            .iter()
+            .find(|charset| charset.to_string() == s)
+            .cloned()
+            .ok_or_else(|| Error::InvalidFormat(format!("unknown charset `{}`", s)))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_charsets() {
+        for (charset, len) in PrimaryCharset::BUILTIN
+            .iter()
+            .zip([8105, 3755, 5401, 2965, 11172])
+        {
+            let chars = charset.chars().unwrap();
+            assert_eq!(chars.len(), len, "{}", charset);
+            let mut unique = chars.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), len, "{} has duplicates", charset);
+            assert_eq!(
+                charset.to_string().parse::<PrimaryCharset>().unwrap(),
+                *charset
+            );
+        }
+
+        // 与最初实现的范围限制一致
+        let tongyong = PrimaryCharset::Tongyong8105;
+        assert_eq!(tongyong.max_start(), 7600);
+        assert!(tongyong.slice(7600, 504).is_ok());
+        assert!(tongyong.slice(7601, 501).is_err());
+        assert!(tongyong.slice(7000, 1106).is_err());
+        assert!(tongyong.slice(6, 500).is_err());
+    }
+
+    #[test]
+    fn test_custom_charset() {
+        let text = format!(
+            "# 测试字集\n{}\n",
+            TONGYONG_8105.chars().rev().take(600).collect::<String>()
+        );
+        let custom = CustomCharset::parse(&text).unwrap();
+        let charset = PrimaryCharset::Custom(custom.clone());
+        assert_eq!(charset.char_count(), 600);
+
+        let recorded = charset.to_string().parse::<PrimaryCharset>().unwrap();
+        let PrimaryCharset::Custom(recorded) = recorded else {
+            panic!("not a custom charset");
+        };
+        assert!(!recorded.is_loaded());
+        assert!(PrimaryCharset::Custom(recorded.clone()).chars().is_err());
+        assert_eq!(recorded.load(&text).unwrap(), custom);
+
+        let other = TONGYONG_8105.chars().take(600).collect::<String>();
+        assert!(matches!(
+            recorded.load(&other),
+            Err(Error::CharsetMismatch { .. })
+        ));
+        assert!(CustomCharset::parse("问问").is_err());
+        assert!(CustomCharset::parse("问抖").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_recovery_card_primary_charset() {
+        use crate::{
+            RecoveryCard, Scheme, SchemeVersion, generate_recovery_card, generate_words32_with,
+            import_recovery_card,
+        };
+
+        let sk = [23u8; 32];
+        let text = "가나다라마바사아자차카타파하";
+        let hangul = ('\u{AC00}'..='\u{D7A3}').step_by(7).collect::<String>();
+        for primary in [
+            PrimaryCharset::Big5Common,
+            PrimaryCharset::Custom(CustomCharset::parse(&hangul).unwrap()),
+        ] {
+            let scheme = Scheme {
+                primary,
+                ..Scheme::new(SchemeVersion::V2, 100, 600)
+            };
+            let card = generate_recovery_card(text, &scheme, Some(&sk)).unwrap();
+            assert_eq!(import_recovery_card(&card, text).unwrap(), sk.to_vec());
+
+            // 恢复卡只记录自备字集的 SHA256，需要重新载入字集文件
+            let parsed = card.to_string().parse::<RecoveryCard>().unwrap();
+            if let PrimaryCharset::Custom(custom) = &parsed.scheme.primary {
+                assert!(import_recovery_card(&parsed, text).is_err());
+                let mut loaded = parsed.clone();
+                loaded.scheme.primary = PrimaryCharset::Custom(custom.load(&hangul).unwrap());
+                assert_eq!(import_recovery_card(&loaded, text).unwrap(), sk.to_vec());
+            }
+        }
+
+        // v1 只支持默认字集
+        let scheme = Scheme {
+            primary: PrimaryCharset::Hangul,
+            ..Scheme::v1(6, 666)
+        };
+        assert!(generate_words32_with("不立文字", &scheme, Some(&sk)).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! 按位打包：把字节串编码为任意 2 的幂进制的符号，每个符号对应心印字表中的一个字
+//!
+//! 心印字表有 2^k 个字时，每个字表示 k 位。字节串按高位在前依次切成 k 位一段，最后一段不足 k 位时补 0。
+//! 256 个字时每个字恰好对应一个字节，与最初的编码完全一致。
+//!
+//! k 大于 8 时，相邻的两个字节长度可能打包成同样多的字（如 k = 10 时 4 字节和 5 字节都是 4 个字），
+//! 还原时一律取较短的长度，生成时拒绝较长的那个，见 [`is_packable`]。
+
+// 打包只在 generate 中使用，解包只在 import 中使用
+#![cfg_attr(not(all(feature = "generate", feature = "import")), allow(dead_code))]
+
+use std::ops::Range;
+
+/// 可选的心印字表大小，默认为 256
+pub const RADIXES: &[usize] = &[64, 128, 256, 512, 1024, 2048, 4096];
+
+/// 心印字表大小对应的位数，不在 [`RADIXES`] 中时返回 `None`
+pub(super) fn bits_of(radix: usize) -> Option<usize> {
+    RADIXES
+        .contains(&radix)
+        .then(|| radix.trailing_zeros() as usize)
+}
+
+/// `byte_len` 个字节打包后的字数
+pub(super) fn symbols_len(byte_len: usize, bits: usize) -> usize {
+    (byte_len * 8).div_ceil(bits)
+}
+
+/// `symbols_len` 个字还原出的字节数：打包后恰好是这么多字的最短字节长度
+pub(super) fn byte_len(symbols_len: usize, bits: usize) -> usize {
+    match symbols_len {
+        0 => 0,
+        n => (n - 1) * bits / 8 + 1,
+    }
+}
+
+/// `byte_len` 个字节打包后能否无歧义地还原
+pub(super) fn is_packable(byte_len: usize, bits: usize) -> bool {
+    self::byte_len(symbols_len(byte_len, bits), bits) == byte_len
+}
+
+/// 把字节串打包为符号，每个符号小于 2^bits
+pub(super) fn pack(bytes: &[u8], bits: usize) -> Vec<usize> {
+    let mut symbols = Vec::with_capacity(symbols_len(bytes.len(), bits));
+    let (mut acc, mut acc_bits) = (0usize, 0);
+    for &byte in bytes {
+        acc = (acc << 8) | byte as usize;
+        acc_bits += 8;
+        while acc_bits >= bits {
+            acc_bits -= bits;
+            symbols.push(acc >> acc_bits);
+            acc &= (1 << acc_bits) - 1;
+        }
+    }
+    if acc_bits > 0 {
+        symbols.push(acc << (bits - acc_bits));
+    }
+    symbols
+}
+
+/// 把符号还原为字节串，长度见 [`byte_len`]，多出的补位被忽略
+pub(super) fn unpack(symbols: &[usize], bits: usize) -> Vec<u8> {
+    let len = byte_len(symbols.len(), bits);
+    let mut bytes = Vec::with_capacity(len);
+    let (mut acc, mut acc_bits) = (0usize, 0);
+    for &symbol in symbols {
+        acc = (acc << bits) | symbol;
+        acc_bits += bits;
+        while acc_bits >= 8 && bytes.len() < len {
+            acc_bits -= 8;
+            bytes.push((acc >> acc_bits) as u8);
+            acc &= (1 << acc_bits) - 1;
+        }
+    }
+    bytes
+}
+
+/// 第 `symbol` 个字所含的位落在哪些字节中
+pub(super) fn bytes_of_symbol(symbol: usize, bits: usize, byte_len: usize) -> Range<usize> {
+    let start = symbol * bits / 8;
+    let end = ((symbol + 1) * bits).div_ceil(8).min(byte_len);
+    start..end.max(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let bytes = (0..=255u8).rev().step_by(3).collect::<Vec<u8>>();
+        for &radix in RADIXES {
+            let bits = bits_of(radix).unwrap();
+            for len in 1..bytes.len() {
+                let symbols = pack(&bytes[..len], bits);
+                assert_eq!(symbols.len(), symbols_len(len, bits));
+                assert!(symbols.iter().all(|&s| s < radix));
+                if is_packable(len, bits) {
+                    assert_eq!(unpack(&symbols, bits), &bytes[..len], "radix {}", radix);
+                }
+            }
+        }
+        assert_eq!(pack(&[0x12, 0x34], 8), vec![0x12, 0x34]);
+        assert_eq!(pack(&[0xff, 0x00, 0x80], 6), vec![63, 48, 2, 0]);
+        assert_eq!(bits_of(300), None);
+    }
+
+    #[test]
+    fn test_lengths() {
+        // 32 字节的密钥
+        let lens = RADIXES
+            .iter()
+            .map(|&radix| symbols_len(32, bits_of(radix).unwrap()))
+            .collect::<Vec<usize>>();
+        assert_eq!(lens, vec![43, 37, 32, 29, 26, 24, 22]);
+        assert!(is_packable(32, 12));
+        assert!(!is_packable(33, 12));
+        assert!((1..300).all(|len| is_packable(len, 6) && is_packable(len, 8)));
+
+        assert_eq!(bytes_of_symbol(1, 10, 5), 1..3);
+        assert_eq!(bytes_of_symbol(3, 10, 5), 3..5);
+        assert_eq!(bytes_of_symbol(2, 8, 5), 2..3);
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_radix() {
+        use crate::{
+            Scheme, SchemeVersion, generate_words32_with, import_words32_repair,
+            import_words32_with,
+        };
+
+        let sk = [41u8; 32];
+        for (radix, len) in [(64, 55), (1024, 33), (4096, 28)] {
+            // 一个字的位可能落在两个字节中，纠错字要多一些
+            let scheme = Scheme {
+                radix,
+                checksum: 1,
+                parity: 8,
+                ..Scheme::new(SchemeVersion::V2, 1, 8104)
+            };
+            let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+            assert_eq!(words.chars().count(), len + 1, "radix {}", radix);
+            assert_eq!(
+                import_words32_with(&words, "不立文字", &scheme).unwrap(),
+                sk
+            );
+
+            // 一个字抄错、一个字无法辨认
+            let mut chars = words.chars().collect::<Vec<char>>();
+            chars[3] = chars[4];
+            chars[len - 1] = '?';
+            let (key, repaired) =
+                import_words32_repair(&chars.iter().collect::<String>(), "不立文字", &scheme)
+                    .unwrap();
+            assert_eq!(key, sk);
+            assert_eq!(repaired, vec![2, len - 2]);
+
+            // 256 字的方案无法还原
+            let plain = Scheme {
+                radix: 256,
+                ..scheme.clone()
+            };
+            assert!(import_words32_with(&words, "不立文字", &plain).is_err());
+        }
+
+        // 4096 字时 33 字节与 32 字节同为 22 个字，无法区分
+        let scheme = Scheme {
+            radix: 4096,
+            checksum: 1,
+            ..Scheme::new(SchemeVersion::V2, 1, 8104)
+        };
+        assert!(generate_words32_with("不立文字", &scheme, Some(&sk)).is_err());
+        let v1 = Scheme {
+            radix: 1024,
+            ..Scheme::v1(1, 8104)
+        };
+        assert!(generate_words32_with("不立文字", &v1, Some(&sk)).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+//! GF(256) 上的 Reed–Solomon 纠错码
+//!
+//! 多项式按高次项在前的顺序存放，生成多项式的根为 2^0, 2^1, ..., 2^(nsym-1)。
+//! `nsym` 个校验字节最多可以纠正 e 个错误和 f 个擦除（位置已知的错误），只要 2e + f <= nsym。
+
+// 编码只在 generate 中使用，纠错只在 import 中使用
+#![cfg_attr(not(all(feature = "generate", feature = "import")), allow(dead_code))]
+
+use super::gf256;
+use crate::{Error, Result};
+
+/// 码字（数据 + 校验）的最大长度
+pub(super) const MAX_CODEWORD_LEN: usize = 255;
+
+/// 纠错字最多的个数
+pub const MAX_PARITY_WORDS: usize = 32;
+
+fn poly_scale(p: &[u8], x: u8) -> Vec<u8> {
+    p.iter().map(|&c| gf256::mul(c, x)).collect()
+}
+
+fn poly_add(p: &[u8], q: &[u8]) -> Vec<u8> {
+    let len = p.len().max(q.len());
+    let mut r = vec![0u8; len];
+    for (i, &c) in p.iter().enumerate() {
+        r[i + len - p.len()] = c;
+    }
+    for (i, &c) in q.iter().enumerate() {
+        r[i + len - q.len()] ^= c;
+    }
+    r
+}
+
+fn poly_mul(p: &[u8], q: &[u8]) -> Vec<u8> {
+    let mut r = vec![0u8; p.len() + q.len() - 1];
+    for (j, &qc) in q.iter().enumerate() {
+        for (i, &pc) in p.iter().enumerate() {
+            r[i + j] ^= gf256::mul(pc, qc);
+        }
+    }
+    r
+}
+
+fn poly_eval(p: &[u8], x: u8) -> u8 {
+    p.iter().fold(0, |y, &c| gf256::mul(y, x) ^ c)
+}
+
+/// 返回 dividend 除以 divisor 的余数，divisor 必须是首一多项式
+fn poly_rem(dividend: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut out = dividend.to_vec();
+    for i in 0..dividend.len().saturating_sub(divisor.len() - 1) {
+        let coef = out[i];
+        if coef != 0 {
+            for (j, &d) in divisor.iter().enumerate().skip(1) {
+                out[i + j] ^= gf256::mul(d, coef);
+            }
+        }
+    }
+    out.split_off(dividend.len() - (divisor.len() - 1))
+}
+
+fn generator_poly(nsym: usize) -> Vec<u8> {
+    (0..nsym).fold(vec![1], |g, i| poly_mul(&g, &[1, gf256::pow(2, i as i32)]))
+}
+
+/// 计算 `data` 的 `nsym` 个校验字节
+pub(super) fn encode(data: &[u8], nsym: usize) -> Vec<u8> {
+    let mut msg = data.to_vec();
+    msg.resize(data.len() + nsym, 0);
+    poly_rem(&msg, &generator_poly(nsym))
+}
+
+fn syndromes(codeword: &[u8], nsym: usize) -> Vec<u8> {
+    (0..nsym)
+        .map(|i| poly_eval(codeword, gf256::pow(2, i as i32)))
+        .collect()
+}
+
+/// 由错误位置（按系数次数计）求错误定位多项式
+fn errata_locator(coef_pos: &[usize]) -> Vec<u8> {
+    coef_pos.iter().fold(vec![1], |loc, &p| {
+        poly_mul(&loc, &[gf256::pow(2, p as i32), 1])
+    })
+}
+
+/// 消去擦除位置后的 Forney 伴随式
+fn forney_syndromes(synd: &[u8], erasures: &[usize], len: usize) -> Vec<u8> {
+    let mut fsynd = synd.to_vec();
+    for &pos in erasures {
+        let x = gf256::pow(2, (len - 1 - pos) as i32);
+        for j in 0..fsynd.len() - 1 {
+            fsynd[j] = gf256::mul(fsynd[j], x) ^ fsynd[j + 1];
+        }
+    }
+    fsynd
+}
+
+/// Berlekamp–Massey 求错误定位多项式（低次项在前）
+fn error_locator(fsynd: &[u8], nsym: usize, erasure_count: usize) -> Result<Vec<u8>> {
+    let mut err_loc = vec![1u8];
+    let mut old_loc = vec![1u8];
+
+    for k in 0..nsym - erasure_count {
+        let mut delta = fsynd[k];
+        for j in 1..err_loc.len() {
+            delta ^= gf256::mul(err_loc[err_loc.len() - 1 - j], fsynd[k - j]);
+        }
+
+        old_loc.push(0);
+        if delta != 0 {
+            if old_loc.len() > err_loc.len() {
+                let new_loc = poly_scale(&old_loc, delta);
+                old_loc = poly_scale(&err_loc, gf256::inverse(delta));
+                err_loc = new_loc;
+            }
+            err_loc = poly_add(&err_loc, &poly_scale(&old_loc, delta));
+        }
+    }
+
+    let leading_zeros = err_loc.iter().take_while(|&&c| c == 0).count();
+    err_loc.drain(..leading_zeros);
+
+    let Some(errors) = err_loc.len().checked_sub(1) else {
+        return Err(Error::Uncorrectable("no error locator".to_string()));
+    };
+    if errors * 2 + erasure_count > nsym {
+        return Err(Error::Uncorrectable(format!(
+            "more than {} parity characters can correct",
+            nsym
+        )));
+    }
+
+    err_loc.reverse();
+    Ok(err_loc)
+}
+
+/// Chien 搜索：求错误定位多项式的根，返回错误在码字中的下标
+fn find_errors(err_loc: &[u8], len: usize) -> Result<Vec<usize>> {
+    let errors = err_loc.len() - 1;
+    let positions = (0..len)
+        .filter(|&i| poly_eval(err_loc, gf256::pow(2, i as i32)) == 0)
+        .map(|i| len - 1 - i)
+        .collect::<Vec<usize>>();
+
+    if positions.len() != errors {
+        return Err(Error::Uncorrectable(format!(
+            "found {} of {} error positions",
+            positions.len(),
+            errors
+        )));
+    }
+    Ok(positions)
+}
+
+/// Forney 算法：求出错误值并修正码字
+fn correct_errata(codeword: &mut [u8], synd: &[u8], positions: &[usize]) {
+    let len = codeword.len();
+    let coef_pos = positions.iter().map(|&p| len - 1 - p).collect::<Vec<_>>();
+    let err_loc = errata_locator(&coef_pos);
+
+    // Ω(x) = S(x) Λ(x) mod x^(ν+1)，S(x) 的常数项为 0
+    let mut synd_rev = synd.to_vec();
+    synd_rev.reverse();
+    synd_rev.push(0);
+    let mut modulus = vec![0u8; err_loc.len() + 1];
+    modulus[0] = 1;
+    let err_eval = poly_rem(&poly_mul(&synd_rev, &err_loc), &modulus);
+
+    let x = coef_pos
+        .iter()
+        .map(|&p| gf256::pow(2, p as i32))
+        .collect::<Vec<u8>>();
+
+    for (i, &xi) in x.iter().enumerate() {
+        let xi_inv = gf256::inverse(xi);
+
+        let err_loc_prime = x
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .fold(1, |acc, (_, &xj)| {
+                gf256::mul(acc, 1 ^ gf256::mul(xi_inv, xj))
+            });
+
+        let y = gf256::mul(xi, poly_eval(&err_eval, xi_inv));
+        codeword[positions[i]] ^= gf256::div(y, err_loc_prime);
+    }
+}
+
+/// 纠正码字中的错误，返回被修正的下标
+///
+/// * `codeword`: 数据 + `nsym` 个校验字节，擦除位置上的值会被忽略
+/// * `erasures`: 已知出错（如无法辨认）的下标
+pub(super) fn correct(codeword: &mut [u8], nsym: usize, erasures: &[usize]) -> Result<Vec<usize>> {
+    if codeword.len() > MAX_CODEWORD_LEN {
+        return Err(Error::WrongLength(format!(
+            "codeword length({}) must not exceed {}",
+            codeword.len(),
+            MAX_CODEWORD_LEN
+        )));
+    }
+    if erasures.len() > nsym {
+        return Err(Error::Uncorrectable(format!(
+            "{} unreadable characters, but only {} parity characters",
+            erasures.len(),
+            nsym
+        )));
+    }
+
+    let received = codeword.to_vec();
+    for &pos in erasures {
+        codeword[pos] = 0;
+    }
+
+    let synd = syndromes(codeword, nsym);
+    if synd.iter().any(|&s| s != 0) {
+        let fsynd = forney_syndromes(&synd, erasures, codeword.len());
+        let err_loc = error_locator(&fsynd, nsym, erasures.len())?;
+        let mut positions = find_errors(&err_loc, codeword.len())?;
+        positions.extend_from_slice(erasures);
+
+        correct_errata(codeword, &synd, &positions);
+
+        if syndromes(codeword, nsym).iter().any(|&s| s != 0) {
+            return Err(Error::Uncorrectable(format!(
+                "more than {} parity characters can correct",
+                nsym
+            )));
+        }
+    }
+
+    Ok((0..codeword.len())
+        .filter(|&i| codeword[i] != received[i])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codeword(nsym: usize) -> Vec<u8> {
+        let data = (0..34u8).map(|i| i.wrapping_mul(37)).collect::<Vec<u8>>();
+        let mut codeword = data.clone();
+        codeword.extend(encode(&data, nsym));
+        codeword
+    }
+
+    #[test]
+    fn test_encode_is_codeword() {
+        let codeword = codeword(6);
+        assert!(syndromes(&codeword, 6).iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn test_correct_errors() {
+        let original = codeword(6);
+        let mut received = original.clone();
+        received[0] ^= 0x11;
+        received[17] = 0;
+        received[39] ^= 0xff;
+
+        let repaired = correct(&mut received, 6, &[]).unwrap();
+        assert_eq!(received, original);
+        assert_eq!(repaired, vec![0, 17, 39]);
+    }
+
+    #[test]
+    fn test_correct_erasures() {
+        let original = codeword(6);
+        let mut received = original.clone();
+        for pos in [1, 5, 9, 20] {
+            received[pos] = 0xaa;
+        }
+        received[30] ^= 1;
+
+        let repaired = correct(&mut received, 6, &[1, 5, 9, 20]).unwrap();
+        assert_eq!(received, original);
+        assert_eq!(repaired, vec![1, 5, 9, 20, 30]);
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let mut received = codeword(4);
+        for pos in [2, 4, 6] {
+            received[pos] ^= 0x5a;
+        }
+        assert!(correct(&mut received, 4, &[]).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_repair() {
+        use crate::{Scheme, generate_words32_with, import_words32_repair};
+
+        let scheme = Scheme {
+            checksum: 1,
+            parity: 4,
+            ..Scheme::v1(6, 666)
+        };
+        let sk = [3u8; 32];
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+        assert_eq!(words.chars().count(), 37);
+
+        let mut chars = words.chars().collect::<Vec<char>>();
+        chars[3] = '?';
+        chars[20] = '？';
+        chars[35] = if chars[35] == '问' { '抖' } else { '问' };
+        let damaged = chars.into_iter().collect::<String>();
+
+        let (key, repaired) = import_words32_repair(&damaged, "不立文字", &scheme).unwrap();
+        assert_eq!(key, sk);
+        assert_eq!(repaired, vec![3, 20, 35]);
+    }
+}
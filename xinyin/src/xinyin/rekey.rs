@@ -0,0 +1,238 @@
+//! 更换心印或方案参数：解密旧的助记字，再用新的心印和方案加密
+//!
+//! 心印可能泄露时，不必先导入、再把密钥逐个字节输入生成：一步完成解密和重新加密，
+//! 解密出的密钥和派生的 AES 密钥只保存在用后清零的内存中，并核对新的助记字能还原出同一个密钥。
+
+use zeroize::Zeroizing;
+
+use crate::sks::{address_of, verify_address};
+use crate::{Error, Result};
+
+use super::generate::generate_words;
+use super::import::decode;
+use super::scheme::Scheme;
+
+/// 解密出的密钥，释放时清零
+type Secret = Zeroizing<Vec<u8>>;
+
+/// 用新的心印和方案重新加密旧助记字中的 32 字节密钥，返回新的助记字和钱包地址
+///
+/// 有 `expected_address` 时先核对旧助记字还原出的密钥，地址不符时返回 [`Error::AddressMismatch`]，
+/// 不会生成新的助记字；新的助记字生成后再按新的心印和方案导入一次，见 [`Error::SecretMismatch`]。
+pub fn rekey_words32(
+    old_words: &str,
+    old_heartprint: &str,
+    old_scheme: &Scheme,
+    new_heartprint: &str,
+    new_scheme: &Scheme,
+    expected_address: Option<&str>,
+) -> Result<(String, String)> {
+    let (new_words, secret_key) = rekey(
+        old_words,
+        old_heartprint,
+        old_scheme,
+        new_heartprint,
+        new_scheme,
+        Some(32),
+        expected_address,
+    )?;
+    Ok((new_words, address_of(&*key32(&secret_key)?)))
+}
+
+/// 用新的心印和方案重新加密旧助记字中任意长度的密钥，见 [`generate_words`](crate::generate_words)
+///
+/// 返回新的助记字和密钥，密钥在释放时清零，32 字节时调用方可以据此显示钱包地址。
+/// `expected_address` 的用法与 [`rekey_words32`] 相同，只能用于 32 字节的密钥，否则返回 [`Error::WrongLength`]。
+pub fn rekey_words(
+    old_words: &str,
+    old_heartprint: &str,
+    old_scheme: &Scheme,
+    new_heartprint: &str,
+    new_scheme: &Scheme,
+    expected_address: Option<&str>,
+) -> Result<(String, Secret)> {
+    rekey(
+        old_words,
+        old_heartprint,
+        old_scheme,
+        new_heartprint,
+        new_scheme,
+        None,
+        expected_address,
+    )
+}
+
+/// 解密并核对钱包地址，重新加密后再按新的心印和方案导入一次，返回新的助记字和密钥
+fn rekey(
+    old_words: &str,
+    old_heartprint: &str,
+    old_scheme: &Scheme,
+    new_heartprint: &str,
+    new_scheme: &Scheme,
+    secret_len: Option<usize>,
+    expected_address: Option<&str>,
+) -> Result<(String, Secret)> {
+    let (secret, _) = decode(old_words, old_heartprint, old_scheme, secret_len)?;
+    if let Some(expected_address) = expected_address {
+        verify_address(&*key32(&secret)?, expected_address)?;
+    }
+    let new_words = generate_words(new_heartprint, new_scheme, &secret)?;
+    let (imported, _) = decode(&new_words, new_heartprint, new_scheme, secret_len)?;
+    if imported != secret {
+        return Err(Error::SecretMismatch);
+    }
+    Ok((new_words, secret))
+}
+
+/// 钱包密钥，返回的数组在释放时清零
+fn key32(secret: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    <[u8; 32]>::try_from(secret)
+        .map(Zeroizing::new)
+        .map_err(|_| Error::WrongLength("secret key must be 32 bytes".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        SchemeVersion, generate_words, generate_words32_with, import_words, import_words32_with,
+    };
+
+    #[test]
+    fn test_rekey_words32() {
+        let secret_key = [7u8; 32];
+        let old_scheme = Scheme::v1(1000, 512);
+        let old_words =
+            generate_words32_with("星辰大海，心自无疆。", &old_scheme, Some(&secret_key)).unwrap();
+
+        let new_scheme = Scheme {
+            checksum: 2,
+            label: "0".to_string(),
+            ..Scheme::new(SchemeVersion::LATEST, 2000, 1024)
+        };
+        let new_heartprint = "外婆家后院那棵歪脖子枣树";
+        let (new_words, address) = rekey_words32(
+            &old_words,
+            "星辰大海，心自无疆。",
+            &old_scheme,
+            new_heartprint,
+            &new_scheme,
+            None,
+        )
+        .unwrap();
+        assert_eq!(address, address_of(&secret_key));
+        assert_eq!(
+            import_words32_with(&new_words, new_heartprint, &new_scheme).unwrap(),
+            secret_key
+        );
+        // 旧的心印不能还原新的助记字
+        assert!(import_words32_with(&new_words, "星辰大海，心自无疆。", &new_scheme).is_err());
+
+        // 前两个字抄反了，都在字表中，没有校验字时也能解密，只有核对地址才能发现
+        let expected = address_of(&secret_key);
+        assert!(
+            rekey_words32(
+                &old_words,
+                "星辰大海，心自无疆。",
+                &old_scheme,
+                new_heartprint,
+                &new_scheme,
+                Some(&expected),
+            )
+            .is_ok()
+        );
+        let mut swapped = old_words.chars().collect::<Vec<char>>();
+        swapped.swap(0, 1);
+        let err = rekey_words32(
+            &swapped.into_iter().collect::<String>(),
+            "星辰大海，心自无疆。",
+            &old_scheme,
+            new_heartprint,
+            &new_scheme,
+            Some(&expected),
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "address_mismatch");
+
+        // 新方案不合法时不会生成新的助记字
+        let invalid = Scheme {
+            label: "0".to_string(),
+            ..Scheme::v1(1000, 512)
+        };
+        let err = rekey_words32(
+            &old_words,
+            "星辰大海，心自无疆。",
+            &old_scheme,
+            new_heartprint,
+            &invalid,
+            None,
+        );
+        assert!(matches!(err, Err(Error::InvalidParams(_))), "{:?}", err);
+    }
+
+    #[test]
+    fn test_rekey_words() {
+        let secret = (0..64u8).map(|i| i.wrapping_mul(5)).collect::<Vec<u8>>();
+        let old_scheme = Scheme {
+            checksum: 1,
+            ..Scheme::new(SchemeVersion::V2, 1000, 512)
+        };
+        let old_words = generate_words("星辰大海，心自无疆。", &old_scheme, &secret).unwrap();
+
+        let new_scheme = Scheme {
+            parity: 4,
+            radix: 1024,
+            ..Scheme::new(SchemeVersion::LATEST, 2000, 1024)
+        };
+        let new_heartprint = "外婆家后院那棵歪脖子枣树";
+        let (new_words, rekeyed) = rekey_words(
+            &old_words,
+            "星辰大海，心自无疆。",
+            &old_scheme,
+            new_heartprint,
+            &new_scheme,
+            None,
+        )
+        .unwrap();
+        assert_eq!(*rekeyed, secret);
+        assert_eq!(
+            import_words(&new_words, new_heartprint, &new_scheme).unwrap(),
+            secret
+        );
+
+        // 旧的心印有误时不会生成新的助记字
+        assert!(
+            rekey_words(
+                &old_words,
+                "星辰大海，心字无疆。",
+                &old_scheme,
+                new_heartprint,
+                &new_scheme,
+                None,
+            )
+            .is_err()
+        );
+
+        // 不是 32 字节的密钥不能按钱包密钥更换
+        let err = rekey_words32(
+            &old_words,
+            "星辰大海，心自无疆。",
+            &old_scheme,
+            new_heartprint,
+            &new_scheme,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "wrong_length");
+        let err = rekey_words(
+            &old_words,
+            "星辰大海，心自无疆。",
+            &old_scheme,
+            new_heartprint,
+            &new_scheme,
+            Some("2gWUYMLPqVpqXnRdrF8h1s1p3y3C2xtrxQjyEzHgbEBG"),
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "wrong_length");
+    }
+}
@@ -0,0 +1,538 @@
+use crate::{Error, Result};
+
+use super::charinfo::CharsetFilter;
+use super::charset_256::{
+    exclude_confusables, exclude_homophones, generate_256_words, generate_charset_from_seed,
+};
+use super::checksum::MAX_CHECKSUM_WORDS;
+use super::heartprint::normalize_heartprint;
+use super::idioms;
+use super::primary::PrimaryCharset;
+use super::radix;
+use super::reed_solomon::{MAX_CODEWORD_LEN, MAX_PARITY_WORDS};
+use super::utils::{AesKey, generate_cbc_key_iv, generate_key_iv_seed};
+#[cfg(feature = "generate")]
+use super::{checksum::checksum, reed_solomon};
+use crate::kdf::Argon2Params;
+
+/// 版本标记字所在的区间：㊀(U+3280) ..= ㊉(U+3289)
+///
+/// 这些字不在任何基础字集中，放在助记字最前面也不会和心印字表里的字混淆。
+pub(super) const MARKER_RANGE: std::ops::RangeInclusive<char> = '\u{3280}'..='\u{3289}';
+
+/// 随机盐的长度
+#[cfg(feature = "generate")]
+const SALT_LEN: usize = 16;
+
+/// 心印方案版本
+///
+/// 版本决定密钥派生（KDF）、加密算法和心印字表的生成方式，一旦发布就不能再改变。
+/// v1 之后的版本在助记字最前面加一个版本标记字（如 `㊁` 表示 v2），导入时据此自动选择方案；
+/// 没有标记字的助记字一律按 v1 处理，保证已有备份可以还原。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemeVersion {
+    /// 最初的方案：
+    /// * Argon2 派生 AES 密钥（默认参数），SHA256 派生 CBC IV
+    /// * AES-256-CBC 加密原始密钥
+    /// * SHA256(心印) 作为 ChaCha20 种子，从基础字集中选出 256 个字
+    ///
+    /// 助记字不带版本标记。
+    ///
+    /// 字表只依赖 SHA256(心印)，拿到助记字的人可以按 SHA256 的速度验证猜测的心印，绕过了 Argon2，
+    /// 见 [`SchemeVersion::is_weak`]。
+    #[default]
+    V1,
+    /// * 一次 Argon2 派生出 AES 密钥、CBC IV 和字表种子
+    /// * AES-256-CBC 加密原始密钥
+    /// * 以 Argon2 派生的种子作为 ChaCha20 种子，从基础字集中选出 256 个字
+    ///
+    /// 版本标记为 `㊁`。
+    V2,
+    /// 与 v2 相同，但派生前先规范化心印（NFC、全角半角、首尾空白，可选去掉标点），
+    /// 换一种输入法或设备输入同一句心印也能还原，见 [`normalize_heartprint`](crate::normalize_heartprint)
+    ///
+    /// 心印本来就是规范形式时，派生出的密钥和字表与 v2 相同。版本标记为 `㊂`。
+    V3,
+}
+
+impl SchemeVersion {
+    /// 所有已发布的版本，按版本号排列
+    pub const ALL: &'static [SchemeVersion] =
+        &[SchemeVersion::V1, SchemeVersion::V2, SchemeVersion::V3];
+
+    /// 新生成的备份默认使用的版本
+    pub const LATEST: SchemeVersion = SchemeVersion::V3;
+
+    /// 版本号，从 1 开始
+    pub fn number(self) -> u8 {
+        match self {
+            SchemeVersion::V1 => 1,
+            SchemeVersion::V2 => 2,
+            SchemeVersion::V3 => 3,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.number() == number)
+    }
+
+    /// 助记字前的版本标记字，v1 没有标记
+    pub fn marker(self) -> Option<char> {
+        match self {
+            SchemeVersion::V1 => None,
+            SchemeVersion::V2 => Some('㊁'),
+            SchemeVersion::V3 => Some('㊂'),
+        }
+    }
+
+    /// 字表派生是否绕过了 Argon2
+    ///
+    /// 为 `true` 时，泄露的助记字可以被用来按 SHA256 的速度暴力猜测心印，建议把密钥迁移到新版本。
+    pub fn is_weak(self) -> bool {
+        match self {
+            SchemeVersion::V1 => true,
+            SchemeVersion::V2 | SchemeVersion::V3 => false,
+        }
+    }
+
+    /// 派生前是否先规范化心印
+    pub fn normalizes_heartprint(self) -> bool {
+        match self {
+            SchemeVersion::V1 | SchemeVersion::V2 => false,
+            SchemeVersion::V3 => true,
+        }
+    }
+
+    pub fn from_marker(marker: char) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|v| v.marker() == Some(marker))
+    }
+
+    /// 从助记字中识别方案版本，返回版本和去掉标记字后的助记字
+    pub fn detect(words: &str) -> Result<(Self, &str)> {
+        let Some(first) = words.chars().next() else {
+            return Ok((SchemeVersion::V1, words));
+        };
+
+        if !MARKER_RANGE.contains(&first) {
+            return Ok((SchemeVersion::V1, words));
+        }
+
+        let version = Self::from_marker(first)
+            .ok_or_else(|| Error::UnsupportedVersion(format!("marker `{}`", first)))?;
+
+        Ok((version, &words[first.len_utf8()..]))
+    }
+}
+
+/// 心印字表中同音字的处理方式
+///
+/// 去掉同音字后，口述助记字（如电话中念给家人）时，听到的读音只对应心印字表中的一个字。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Homophones {
+    /// 保留同音字
+    #[default]
+    Allowed,
+    /// 拼音和声调都相同的字只保留一个
+    DistinctTones,
+    /// 拼音相同的字只保留一个，不论声调，需要较大的选取范围
+    DistinctSyllables,
+}
+
+/// 由心印派生出的心印字表和加密参数
+pub(super) struct Derived {
+    pub charset: Vec<char>,
+    /// 心印成语表，只在成语编码时有，此时 `charset` 为空
+    pub idioms: Vec<&'static str>,
+    pub aes_key: AesKey,
+    pub aes_iv: [u8; 16],
+}
+
+/// 心印方案：除心印和密钥之外，生成和还原助记字所需的全部参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheme {
+    pub version: SchemeVersion,
+    /// 基础字集起始位置，1-based
+    pub start: usize,
+    /// 基础字集长度
+    pub count: usize,
+    /// 选取范围所在的基础字集，默认为《通用规范汉字表》；v1 只支持默认字集
+    pub primary: PrimaryCharset,
+    /// 在选取范围内再按字信息选字，如只用一级字；只能用于默认字集，v1 不支持
+    pub filter: Option<CharsetFilter>,
+    /// 去掉形近字（如 `己已巳`、`未末`），每组只保留一个，手抄的备份不会因字迹潦草而认错；v1 不支持
+    pub confusable_free: bool,
+    /// 是否去掉同音字，见 [`Homophones`]；v1 不支持
+    pub homophones: Homophones,
+    /// 心印字表的字数，即编码的进制，见 [`RADIXES`](crate::RADIXES)；v1 只支持 256
+    ///
+    /// 每个字表示 log2(radix) 位：1024 个字时 32 字节的密钥只需 26 个字，64 个字时需要 43 个字，
+    /// 但可以只用最简单的字。校验字和纠错字个数按字节计，和密钥一起打包。
+    pub radix: usize,
+    /// 以四字成语代替单字编码，心印成语表从内置成语表（[`idiom_list`](crate::idiom_list)）的选取范围中选出 `radix` 条；
+    /// 不能与基础字集、选字条件、去掉形近字和同音字同时使用，v1 不支持
+    ///
+    /// 4096 条成语时 32 字节的密钥为 22 条成语，比 32 个互不相关的字好记。
+    pub idioms: bool,
+    /// 规范化心印时去掉全部标点符号和空白，`星辰大海，心自无疆。` 与 `星辰大海心自无疆` 视为同一句心印；
+    /// 只有 v3 及以后的版本支持
+    pub strip_punctuation: bool,
+    /// 派生 AES 密钥的 Argon2 参数，非默认参数需要和助记字一起保存
+    pub argon2: Argon2Params,
+    /// 钱包标签，如 `0`、`1` 或 `家庭`，空字符串表示不加标签
+    ///
+    /// 同一个心印配不同的标签，派生出的密钥和字表互不相关，可以用一句心印保护多个钱包。
+    /// 标签不写入助记字，需要和助记字一起保存；v1 不支持标签。
+    pub label: String,
+    /// 每份备份随机生成的盐，空表示不加盐
+    ///
+    /// 加盐后，即使心印被猜中，没有恢复卡也无法还原；盐记录在恢复卡中。v1 不支持加盐。
+    pub salt: Vec<u8>,
+    /// 附加在助记字末尾的校验字个数，0 表示不加校验字
+    pub checksum: usize,
+    /// 附加在校验字之后的 Reed–Solomon 纠错字个数，0 表示不加纠错字
+    ///
+    /// n 个纠错字最多可以纠正 n/2 个抄错的字，或 n 个无法辨认（写成 `?`）的字。
+    pub parity: usize,
+}
+
+impl Scheme {
+    pub fn new(version: SchemeVersion, start: usize, count: usize) -> Self {
+        Self {
+            version,
+            start,
+            count,
+            primary: PrimaryCharset::default(),
+            filter: None,
+            confusable_free: false,
+            homophones: Homophones::Allowed,
+            radix: 256,
+            idioms: false,
+            strip_punctuation: false,
+            argon2: Argon2Params::default(),
+            label: String::new(),
+            salt: Vec::new(),
+            checksum: 0,
+            parity: 0,
+        }
+    }
+
+    /// 与最初实现完全一致的 v1 方案
+    pub fn v1(start: usize, count: usize) -> Self {
+        Self::new(SchemeVersion::V1, start, count)
+    }
+
+    /// 加上随机生成的 16 字节盐
+    #[cfg(feature = "generate")]
+    pub fn with_random_salt(self) -> Result<Self> {
+        use rand::{TryRngCore, rngs::OsRng};
+
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng
+            .try_fill_bytes(&mut salt)
+            .map_err(|err| Error::RandomFailure(format!("osrng: {}", err)))?;
+        Ok(Self { salt, ..self })
+    }
+
+    /// 按范围、选字条件从基础字集中选出、并按需去掉形近字和同音字后的字，心印字表从中选出 256 个字
+    fn primary_words(&self) -> Result<Vec<char>> {
+        let words = self.primary.slice(self.start, self.count)?;
+        let words = match &self.filter {
+            None => words,
+            Some(_) if self.primary != PrimaryCharset::default() => {
+                return Err(Error::InvalidParams(
+                    "charset filter is only available for the 8105 charset".to_string(),
+                ));
+            }
+            Some(filter) => filter.apply(&words)?,
+        };
+        let words = if self.confusable_free {
+            exclude_confusables(&words)
+        } else {
+            words
+        };
+        Ok(match self.homophones {
+            Homophones::Allowed => words,
+            homophones => exclude_homophones(&words, homophones),
+        })
+    }
+
+    /// 成语编码时按范围从内置成语表中选出的成语，心印成语表从中选出 `radix` 条
+    fn primary_idioms(&self) -> Result<Vec<&'static str>> {
+        if self.primary != PrimaryCharset::default()
+            || self.filter.is_some()
+            || self.confusable_free
+            || self.homophones != Homophones::Allowed
+        {
+            return Err(Error::InvalidParams(
+                "idiom encoding can not be combined with charset options".to_string(),
+            ));
+        }
+        idioms::slice(self.start, self.count)
+    }
+
+    /// 由心印派生心印字表，以及 AES-256-CBC 的密钥和 IV
+    pub(super) fn derive(&self, txt_in_heart: &str) -> Result<Derived> {
+        if self.strip_punctuation && !self.version.normalizes_heartprint() {
+            return Err(Error::InvalidParams(
+                "stripping punctuation from heartprint requires scheme v3 or later".to_string(),
+            ));
+        }
+        let txt_in_heart = &if self.version.normalizes_heartprint() {
+            normalize_heartprint(txt_in_heart, self.strip_punctuation)
+        } else {
+            txt_in_heart.to_string()
+        };
+
+        match self.version {
+            SchemeVersion::V1 => {
+                if !self.label.is_empty() {
+                    return Err(Error::InvalidParams(
+                        "wallet label requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if !self.salt.is_empty() {
+                    return Err(Error::InvalidParams(
+                        "salt requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if self.primary != PrimaryCharset::default() {
+                    return Err(Error::InvalidParams(
+                        "primary charset other than 8105 requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if self.filter.is_some() {
+                    return Err(Error::InvalidParams(
+                        "charset filter requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if self.confusable_free {
+                    return Err(Error::InvalidParams(
+                        "confusable-free charset requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if self.homophones != Homophones::Allowed {
+                    return Err(Error::InvalidParams(
+                        "homophone-free charset requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if self.radix != 256 {
+                    return Err(Error::InvalidParams(
+                        "radix other than 256 requires scheme v2 or later".to_string(),
+                    ));
+                }
+                if self.idioms {
+                    return Err(Error::InvalidParams(
+                        "idiom encoding requires scheme v2 or later".to_string(),
+                    ));
+                }
+                let charset = generate_256_words(txt_in_heart, &self.primary_words()?)?;
+                let (aes_key, aes_iv) = generate_cbc_key_iv(txt_in_heart, &self.argon2)?;
+                Ok(Derived {
+                    charset,
+                    idioms: Vec::new(),
+                    aes_key,
+                    aes_iv,
+                })
+            }
+            SchemeVersion::V2 | SchemeVersion::V3 => {
+                self.radix_bits()?;
+                let (aes_key, aes_iv, seed) =
+                    generate_key_iv_seed(txt_in_heart, &self.label, &self.salt, &self.argon2)?;
+                let (charset, idioms) = if self.idioms {
+                    let idioms = self.primary_idioms()?;
+                    (
+                        Vec::new(),
+                        generate_charset_from_seed(seed, &idioms, self.radix)?,
+                    )
+                } else {
+                    let words = self.primary_words()?;
+                    (
+                        generate_charset_from_seed(seed, &words, self.radix)?,
+                        Vec::new(),
+                    )
+                };
+                Ok(Derived {
+                    charset,
+                    idioms,
+                    aes_key,
+                    aes_iv,
+                })
+            }
+        }
+    }
+
+    /// 每个字表示的位数，`radix` 不在 [`RADIXES`](crate::RADIXES) 中时返回错误
+    pub(super) fn radix_bits(&self) -> Result<usize> {
+        radix::bits_of(self.radix).ok_or_else(|| {
+            Error::InvalidParams(format!(
+                "radix({}) must be one of {:?}",
+                self.radix,
+                radix::RADIXES
+            ))
+        })
+    }
+
+    /// 检查校验字和纠错字的个数；`secret_len` 不为 `None` 且有纠错字时，
+    /// 还检查密钥、校验字和纠错字合起来不超过一个纠错码字，超出时返回 [`Error::InvalidParams`]
+    pub(super) fn check_lengths(&self, secret_len: Option<usize>) -> Result<()> {
+        if self.checksum > MAX_CHECKSUM_WORDS {
+            return Err(Error::InvalidParams(format!(
+                "checksum({}) must not exceed {} characters",
+                self.checksum, MAX_CHECKSUM_WORDS
+            )));
+        }
+        if self.parity > MAX_PARITY_WORDS {
+            return Err(Error::InvalidParams(format!(
+                "parity({}) must not exceed {} characters",
+                self.parity, MAX_PARITY_WORDS
+            )));
+        }
+        if let Some(secret_len) = secret_len
+            && self.parity > 0
+            && secret_len + self.checksum + self.parity > MAX_CODEWORD_LEN
+        {
+            return Err(Error::InvalidParams(format!(
+                "secret({} bytes) with checksum and parity must not exceed {} characters",
+                secret_len, MAX_CODEWORD_LEN
+            )));
+        }
+        Ok(())
+    }
+
+    /// 把加密密钥编码为助记字，带上版本标记、校验字和纠错字；成语编码时成语之间以空格分隔
+    #[cfg(feature = "generate")]
+    pub(super) fn encode(&self, derived: &Derived, encrypted_key: &[u8]) -> Result<String> {
+        let bits = self.radix_bits()?;
+        let mut key_words = String::new();
+        if let Some(marker) = self.version.marker() {
+            key_words.push(marker);
+        }
+
+        let mut bytes = encrypted_key.to_vec();
+        bytes.extend(checksum(encrypted_key, self.checksum));
+        bytes.extend(reed_solomon::encode(&bytes, self.parity));
+        if !radix::is_packable(bytes.len(), bits) {
+            return Err(Error::InvalidParams(format!(
+                "{} bytes of secret, checksum and parity can not be encoded with radix {}, add or remove a parity character",
+                bytes.len(),
+                self.radix
+            )));
+        }
+
+        let symbols = radix::pack(&bytes, bits);
+        if self.idioms {
+            let idioms = &derived.idioms;
+            let idioms = symbols
+                .iter()
+                .map(|&symbol| idioms[symbol % idioms.len()])
+                .collect::<Vec<&str>>();
+            key_words.push_str(&idioms.join(" "));
+        } else {
+            let words = &derived.charset;
+            for symbol in symbols {
+                key_words.push(words[symbol % words.len()]);
+            }
+        }
+        Ok(key_words)
+    }
+
+    /// 按助记字中的版本标记确定实际使用的方案，返回该方案和去掉标记字后的助记字
+    #[cfg(feature = "import")]
+    pub(super) fn detect<'a>(&self, words: &'a str) -> Result<(Self, &'a str)> {
+        let (version, words) = SchemeVersion::detect(words)?;
+        Ok((
+            Self {
+                version,
+                ..self.clone()
+            },
+            words,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_version() {
+        let (version, words) = SchemeVersion::detect("问抖廷亿").unwrap();
+        assert_eq!(version, SchemeVersion::V1);
+        assert_eq!(words, "问抖廷亿");
+
+        let (version, words) = SchemeVersion::detect("㊁问抖廷亿").unwrap();
+        assert_eq!(version, SchemeVersion::V2);
+        assert_eq!(words, "问抖廷亿");
+
+        assert!(SchemeVersion::detect("㊈问抖廷亿").is_err());
+    }
+
+    #[test]
+    fn test_version_number() {
+        for &version in SchemeVersion::ALL {
+            assert_eq!(SchemeVersion::from_number(version.number()), Some(version));
+        }
+        assert_eq!(SchemeVersion::LATEST, *SchemeVersion::ALL.last().unwrap());
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_v2() {
+        use crate::{generate_words32_with, import_words32};
+
+        let sk = [7u8; 32];
+        let scheme = Scheme::new(SchemeVersion::V2, 6, 666);
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+        assert!(words.starts_with('㊁'));
+        assert_eq!(words.chars().count(), 33);
+        assert_eq!(import_words32(&words, "不立文字", 6, 666).unwrap(), sk);
+
+        // 同一个心印，v2 的字表和 v1 不同
+        let v1 = Scheme::v1(6, 666).derive("不立文字").unwrap();
+        let v2 = Scheme::new(SchemeVersion::V2, 6, 666)
+            .derive("不立文字")
+            .unwrap();
+        assert_ne!(v1.charset, v2.charset);
+        assert_ne!(v1.aes_iv, v2.aes_iv);
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_import_words32_label() {
+        use crate::{generate_words32_with, import_words32_with};
+
+        let sk = [5u8; 32];
+        let scheme = Scheme {
+            label: "1".to_string(),
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        let words = generate_words32_with("不立文字", &scheme, Some(&sk)).unwrap();
+        assert_eq!(
+            import_words32_with(&words, "不立文字", &scheme).unwrap(),
+            sk
+        );
+
+        // 标签不同，字表和密钥都不同
+        let other = Scheme {
+            label: "2".to_string(),
+            ..scheme.clone()
+        };
+        assert_ne!(
+            scheme.derive("不立文字").unwrap().charset,
+            other.derive("不立文字").unwrap().charset
+        );
+        assert_ne!(
+            scheme.derive("不立文字").unwrap().aes_key,
+            other.derive("不立文字").unwrap().aes_key
+        );
+
+        let v1 = Scheme {
+            label: "1".to_string(),
+            ..Scheme::v1(6, 666)
+        };
+        assert!(generate_words32_with("不立文字", &v1, Some(&sk)).is_err());
+    }
+}
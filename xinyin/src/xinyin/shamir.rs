@@ -0,0 +1,208 @@
+//! GF(256) 上的 Shamir 秘密分享
+//!
+//! 把密钥拆成 n 份，任意 k 份（门限）即可还原，少于 k 份得不到密钥的任何信息。
+//! 每份的内容为 `[门限, 序号, y...]`，`y` 与密钥等长，序号从 1 开始；
+//! 每份再按心印方案加密、编码为一行助记字。
+
+// 拆分只在 generate 中使用，合并只在 import 中使用
+#![cfg_attr(not(all(feature = "generate", feature = "import")), allow(dead_code))]
+
+use super::gf256;
+use super::scheme::Scheme;
+use crate::{Error, Result};
+use zeroize::Zeroizing;
+
+/// 最多拆分的份数，序号 0 留给密钥本身
+pub const MAX_SHARES: usize = 255;
+
+/// 按拆分的门限和份数检查参数
+fn check_threshold(threshold: usize, shares: usize) -> Result<()> {
+    if threshold < 2 || threshold > shares || shares > MAX_SHARES {
+        return Err(Error::InvalidParams(format!(
+            "threshold({}) must be at least 2 and not exceed shares({}), shares must not exceed {}",
+            threshold, shares, MAX_SHARES
+        )));
+    }
+    Ok(())
+}
+
+/// 拆分密钥，`coefficients` 为 (门限 - 1) × 密钥长度 个随机字节
+///
+/// 返回的份是加密前的明文，在释放时清零。
+fn split(
+    secret: &[u8],
+    threshold: usize,
+    shares: usize,
+    coefficients: &[u8],
+) -> Vec<Zeroizing<Vec<u8>>> {
+    (1..=shares)
+        .map(|index| {
+            let x = index as u8;
+            let mut share = Zeroizing::new(vec![threshold as u8, x]);
+            for (i, &s) in secret.iter().enumerate() {
+                // 多项式 s + a1·x + a2·x² + ...，按 Horner 法从高次项算起
+                let y = coefficients
+                    .chunks_exact(secret.len())
+                    .rev()
+                    .fold(0, |y, coef| gf256::mul(y, x) ^ coef[i]);
+                share.push(gf256::mul(y, x) ^ s);
+            }
+            share
+        })
+        .collect()
+}
+
+/// 由至少门限份数的份还原密钥，还原出的密钥在释放时清零
+fn combine(shares: &[Zeroizing<Vec<u8>>]) -> Result<Zeroizing<Vec<u8>>> {
+    let first = shares.first().ok_or(Error::NotEnoughShares {
+        threshold: 2,
+        got: 0,
+    })?;
+    if first.len() < 3 {
+        return Err(Error::WrongLength(format!(
+            "share must be at least 3 bytes, but got {}",
+            first.len()
+        )));
+    }
+
+    let threshold = first[0] as usize;
+    let mut unique: Vec<&Zeroizing<Vec<u8>>> = Vec::new();
+    for share in shares {
+        if share.len() != first.len() || share[0] as usize != threshold || share[1] == 0 {
+            return Err(Error::InvalidFormat(
+                "shares do not belong to the same secret".to_string(),
+            ));
+        }
+        match unique.iter().find(|s| s[1] == share[1]) {
+            Some(same) if *same != share => {
+                return Err(Error::InvalidFormat(format!(
+                    "two different shares have the same index {}",
+                    share[1]
+                )));
+            }
+            Some(_) => {}
+            None => unique.push(share),
+        }
+    }
+
+    if threshold < 2 || unique.len() < threshold {
+        return Err(Error::NotEnoughShares {
+            threshold,
+            got: unique.len(),
+        });
+    }
+
+    // 拉格朗日插值求 x = 0 处的值
+    let points = &unique[..threshold];
+    let mut secret = Zeroizing::new(vec![0u8; first.len() - 2]);
+    for (j, share) in points.iter().enumerate() {
+        let xj = share[1];
+        let basis = points
+            .iter()
+            .enumerate()
+            .filter(|&(m, _)| m != j)
+            .fold(1, |basis, (_, other)| {
+                gf256::mul(basis, gf256::div(other[1], other[1] ^ xj))
+            });
+        for (s, &y) in secret.iter_mut().zip(&share[2..]) {
+            *s ^= gf256::mul(y, basis);
+        }
+    }
+    Ok(secret)
+}
+
+/// 把任意长度的密钥拆成 `shares` 份助记字，任意 `threshold` 份即可还原
+///
+/// 每份都用同一个心印和方案加密编码，份中带有门限和序号，合并时无需另外记录。
+#[cfg(feature = "generate")]
+pub fn split_words(
+    txt_in_heart: &str,
+    scheme: &Scheme,
+    secret: &[u8],
+    threshold: usize,
+    shares: usize,
+) -> Result<Vec<String>> {
+    use rand::{TryRngCore, rngs::OsRng};
+
+    check_threshold(threshold, shares)?;
+    if secret.is_empty() {
+        return Err(Error::WrongLength("secret must not be empty".to_string()));
+    }
+
+    let mut coefficients = Zeroizing::new(vec![0u8; (threshold - 1) * secret.len()]);
+    OsRng
+        .try_fill_bytes(&mut coefficients)
+        .map_err(|err| Error::RandomFailure(format!("osrng: {}", err)))?;
+
+    split(secret, threshold, shares, &coefficients)
+        .iter()
+        .map(|share| super::generate::generate_words(txt_in_heart, scheme, &share[..]))
+        .collect()
+}
+
+/// 合并 [`split_words`] 拆分出的助记字，还原密钥
+///
+/// 份数少于门限时返回 [`Error::NotEnoughShares`]；重复的份只计一次。还原出的密钥在释放时清零。
+#[cfg(feature = "import")]
+pub fn combine_words<S: AsRef<str>>(
+    shares: &[S],
+    txt_in_heart: &str,
+    scheme: &Scheme,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let shares = shares
+        .iter()
+        .map(|words| {
+            super::import::import_words(words.as_ref(), txt_in_heart, scheme).map(Zeroizing::new)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    combine(&shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_combine() {
+        let secret = (0..32).map(|i| i as u8 * 7).collect::<Vec<u8>>();
+        let coefficients = (0..64u8)
+            .map(|i| i.wrapping_mul(13).wrapping_add(1))
+            .collect::<Vec<u8>>();
+        let shares = split(&secret, 3, 5, &coefficients);
+        assert_eq!(shares.len(), 5);
+        assert_eq!(shares[4][..2], [3, 5]);
+
+        for picked in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset = picked.map(|i| shares[i].clone());
+            assert_eq!(*combine(&subset).unwrap(), secret);
+        }
+
+        let err = combine(&[shares[0].clone(), shares[3].clone(), shares[0].clone()]).unwrap_err();
+        assert_eq!(
+            err,
+            Error::NotEnoughShares {
+                threshold: 3,
+                got: 2
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "generate", feature = "import"))]
+    fn test_split_combine_words() {
+        use super::super::scheme::SchemeVersion;
+
+        let scheme = Scheme {
+            checksum: 1,
+            ..Scheme::new(SchemeVersion::V2, 6, 666)
+        };
+        let secret = [21u8; 32];
+        let shares = split_words("不立文字", &scheme, &secret, 2, 3).unwrap();
+        assert_eq!(shares[0].chars().count(), 1 + 2 + 32 + 1);
+        assert_eq!(
+            combine_words(&shares[1..], "不立文字", &scheme).unwrap()[..],
+            secret
+        );
+        assert!(split_words("不立文字", &scheme, &secret, 1, 3).is_err());
+    }
+}
@@ -0,0 +1,557 @@
+//! 心印强度估计：估计心印的熵，并按 Argon2 参数换算成攻击者的破解成本
+//!
+//! 按攻击者最省力的猜法切分规范化后的心印，各段的位数相加即为估计的熵：
+//!
+//! - 常见语料（诗词名句、常用语、常见密码，见 `heartprint-corpus.txt`）和成语表中的句子，
+//!   按语料的条数计，而不是按字数；
+//! - 其余中文按词频表（`heartprint-words.txt`）切分为词，每个词按出现概率计；
+//! - 年份、日期，连续或重复的字符，如 `123456`、`哈哈哈`；
+//! - 英文字母、数字、标点和其它字符按固定的位数计。
+//!
+//! 词频模型不考虑上下文，按固定比例折算后仍只是粗略的估计，用于提示明显过弱的心印。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use crate::Argon2Params;
+
+use super::heartprint::normalize_heartprint;
+use super::idioms::idiom_list;
+
+const WORDS: &str = include_str!("../../heartprint-words.txt");
+const CORPUS: &str = include_str!("../../heartprint-corpus.txt");
+
+/// 词频模型不考虑上下文，词和字的位数按这个比例折算，接近语言模型对中文句子的估计
+const CONTEXT_FACTOR: f64 = 0.6;
+/// 词频表中多字词的最大字数
+const MAX_WORD_LEN: usize = 4;
+/// 重复片段的最大字数，更长的重复按多段计，避免长文本的切分耗时过长
+const MAX_REPEAT_LEN: usize = 32;
+
+/// 小写英文字母，按英文文本的熵估计
+const LOWERCASE_BITS: f64 = 3.0;
+/// 大写字母多出一位
+const UPPERCASE_BITS: f64 = 4.0;
+const DIGIT_BITS: f64 = 3.32;
+/// 逗号、句号等常见标点，位置大致可以预测
+const COMMON_PUNCTUATION_BITS: f64 = 2.0;
+const WHITESPACE_BITS: f64 = 1.0;
+const SYMBOL_BITS: f64 = 5.0;
+/// 词频表中没有的字，如生僻字、表情符号
+const RARE_CHAR_BITS: f64 = 16.0;
+/// 年份按 1900–2099 计
+const YEAR_BITS: f64 = 7.64;
+const MONTH_BITS: f64 = 3.58;
+const DAY_BITS: f64 = 4.95;
+
+/// 心印的最少字数，少于这个字数总是给出提示
+const MIN_CHARS: usize = 8;
+
+/// 攻击者单台设备（高端 GPU）的内存带宽，单位字节每秒
+const DEVICE_BANDWIDTH: f64 = 1e12;
+/// 租用单台设备一小时的价格，单位美元
+const DEVICE_HOUR_USD: f64 = 2.0;
+/// 破解成本低于这个数的心印为弱，单位美元
+const WEAK_COST_USD: f64 = 1e6;
+/// 破解成本低于这个数的心印为中等，单位美元
+const FAIR_COST_USD: f64 = 1e10;
+
+/// 心印的强度等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StrengthLevel {
+    /// 弱：破解成本低于一百万美元，不应使用
+    Weak,
+    /// 中等：破解成本低于一百亿美元，建议换一句更长的话
+    Fair,
+    /// 强
+    Strong,
+}
+
+impl StrengthLevel {
+    /// 稳定的文本形式：`weak`、`fair`、`strong`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StrengthLevel::Weak => "weak",
+            StrengthLevel::Fair => "fair",
+            StrengthLevel::Strong => "strong",
+        }
+    }
+}
+
+/// 心印中拉低强度的部分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrengthWarning {
+    /// 心印的字数（规范化后，不含空白）少于 8 个
+    TooShort(usize),
+    /// 常见的诗句、成语、常用语或密码
+    KnownPhrase(String),
+    /// 年份或日期
+    Date(String),
+    /// 连续或重复的字符
+    Pattern(String),
+}
+
+impl StrengthWarning {
+    /// 稳定的提示码：`too_short`、`known_phrase`、`date`、`pattern`
+    pub fn code(&self) -> &'static str {
+        match self {
+            StrengthWarning::TooShort(_) => "too_short",
+            StrengthWarning::KnownPhrase(_) => "known_phrase",
+            StrengthWarning::Date(_) => "date",
+            StrengthWarning::Pattern(_) => "pattern",
+        }
+    }
+}
+
+/// 心印强度的估计结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartprintStrength {
+    /// 估计的熵，单位位
+    pub entropy_bits: f64,
+    /// 攻击者平均需要猜测的次数，即 2^(熵 - 1)
+    pub guesses: f64,
+    /// 单台设备每秒能计算的 Argon2 次数
+    pub guesses_per_second: f64,
+    /// 单台设备平均需要的秒数
+    pub crack_seconds: f64,
+    /// 平均破解成本，单位美元
+    pub crack_cost_usd: f64,
+    pub level: StrengthLevel,
+    pub warnings: Vec<StrengthWarning>,
+}
+
+struct Model {
+    /// 词频表中的词和位数
+    words: HashMap<String, f64>,
+    /// 常见语料和成语，英文按小写
+    phrases: HashSet<String>,
+    /// 常见语料中最长一句的字数
+    max_phrase_len: usize,
+    /// 常见语料中每一句的位数
+    phrase_bits: f64,
+}
+
+static MODEL: LazyLock<Model> = LazyLock::new(|| {
+    let mut total = 0.0;
+    let mut freqs = Vec::new();
+    for line in WORDS.lines() {
+        if let Some(value) = line.strip_prefix("# total ") {
+            total = value
+                .parse()
+                .expect("invalid total in heartprint-words.txt");
+        } else if !line.is_empty() && !line.starts_with('#') {
+            let (word, freq) = line
+                .split_once(' ')
+                .and_then(|(word, freq)| Some((word, freq.parse::<f64>().ok()?)))
+                .expect("invalid line in heartprint-words.txt");
+            freqs.push((word.to_string(), freq));
+        }
+    }
+    let words = freqs
+        .into_iter()
+        .map(|(word, freq)| (word, (total / freq).log2() * CONTEXT_FACTOR))
+        .collect();
+
+    let phrases = CORPUS
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_lowercase())
+        .chain(idiom_list().into_iter().map(str::to_string))
+        .collect::<HashSet<String>>();
+    let max_phrase_len = phrases
+        .iter()
+        .map(|phrase| phrase.chars().count())
+        .max()
+        .unwrap_or_default();
+    let phrase_bits = (phrases.len() as f64).log2() + 1.0;
+    Model {
+        words,
+        phrases,
+        max_phrase_len,
+        phrase_bits,
+    }
+});
+
+/// 切分出的一段心印
+#[derive(Clone, Copy, PartialEq)]
+enum Segment {
+    Char,
+    Word,
+    Phrase,
+    Date,
+    /// 连续的字母或数字，如 `123456`、`abcd`
+    Sequence,
+    /// 与紧挨着的前一段相同
+    Repeat,
+}
+
+fn char_bits(ch: char) -> f64 {
+    if let Some(&bits) = MODEL.words.get(ch.encode_utf8(&mut [0; 4]) as &str) {
+        return bits;
+    }
+    match ch {
+        'a'..='z' => LOWERCASE_BITS,
+        'A'..='Z' => UPPERCASE_BITS,
+        '0'..='9' => DIGIT_BITS,
+        ',' | '.' | '!' | '?' | ';' | ':' | '\'' | '"' | '-' => COMMON_PUNCTUATION_BITS,
+        _ if ch.is_whitespace() => WHITESPACE_BITS,
+        _ if ch.is_ascii() => SYMBOL_BITS,
+        _ => RARE_CHAR_BITS,
+    }
+}
+
+/// 从 `i` 起的 1–2 位阿拉伯数字或“三”“十二”“二十一”这样的中文数字
+fn small_number(chars: &[char], i: usize) -> Option<(u32, usize)> {
+    let digits = chars
+        .get(i..)?
+        .iter()
+        .take(2)
+        .take_while(|ch| ch.is_ascii_digit())
+        .count();
+    if digits > 0 {
+        let value = chars[i..i + digits]
+            .iter()
+            .fold(0, |value, ch| value * 10 + ch.to_digit(10).unwrap_or(0));
+        return Some((value, digits));
+    }
+
+    let digit = |j: usize| {
+        chars
+            .get(j)
+            .and_then(|&ch| chinese_digit(ch))
+            .filter(|&d| d > 0)
+    };
+    let (tens, mut len) = match (digit(i), chars.get(i + usize::from(digit(i).is_some()))) {
+        (Some(d), Some('十')) => (d, 2),
+        (None, Some('十')) => (1, 1),
+        (Some(d), _) => return Some((d, 1)),
+        (None, _) => return None,
+    };
+    let mut value = tens * 10;
+    if let Some(d) = digit(i + len) {
+        value += d;
+        len += 1;
+    }
+    Some((value, len))
+}
+
+fn chinese_digit(ch: char) -> Option<u32> {
+    "〇一二三四五六七八九"
+        .chars()
+        .position(|digit| digit == ch)
+        .or((ch == '零').then_some(0))
+        .map(|d| d as u32)
+}
+
+/// 从 `i` 起的四位年份，阿拉伯数字或“二〇二四”这样逐位写的中文数字
+fn year(chars: &[char], i: usize) -> Option<usize> {
+    let digits = chars.get(i..i + 4)?;
+    if chars.get(i + 4).is_some_and(char::is_ascii_digit) {
+        return None;
+    }
+    let value = digits.iter().try_fold(0, |value, &ch| {
+        Some(value * 10 + ch.to_digit(10).or_else(|| chinese_digit(ch))?)
+    })?;
+    (1900..=2099).contains(&value).then_some(i + 4)
+}
+
+/// 从 `i` 起的日期：结束位置和位数，取最长的一种写法
+fn date(chars: &[char], i: usize) -> Option<(usize, f64)> {
+    let month = |j: usize| small_number(chars, j).filter(|&(m, _)| (1..=12).contains(&m));
+    let day = |j: usize| small_number(chars, j).filter(|&(d, _)| (1..=31).contains(&d));
+    let at = |j: usize, ch: char| chars.get(j) == Some(&ch);
+
+    // 19900101
+    if let Some(digits) = chars.get(i..i + 8)
+        && digits.iter().all(char::is_ascii_digit)
+        && !chars.get(i + 8).is_some_and(char::is_ascii_digit)
+        && year(&chars[..i + 4], i).is_some()
+        && month(i + 4).is_some_and(|(_, len)| len == 2)
+        && day(i + 6).is_some_and(|(_, len)| len == 2)
+    {
+        return Some((i + 8, YEAR_BITS + MONTH_BITS + DAY_BITS));
+    }
+
+    if let Some(end) = year(chars, i) {
+        // 1990-01-01、1990/1/1、1990.1.1
+        if let Some(&sep) = chars.get(end)
+            && matches!(sep, '-' | '/' | '.')
+            && let Some((_, m)) = month(end + 1)
+            && at(end + 1 + m, sep)
+            && let Some((_, d)) = day(end + 2 + m)
+        {
+            return Some((end + 2 + m + d, YEAR_BITS + MONTH_BITS + DAY_BITS));
+        }
+        // 1990年1月1日
+        if at(end, '年') {
+            if let Some((_, m)) = month(end + 1)
+                && at(end + 1 + m, '月')
+            {
+                let end = end + 2 + m;
+                if let Some((_, d)) = day(end)
+                    && (at(end + d, '日') || at(end + d, '号'))
+                {
+                    return Some((end + d + 1, YEAR_BITS + MONTH_BITS + DAY_BITS));
+                }
+                return Some((end, YEAR_BITS + MONTH_BITS));
+            }
+            return Some((end + 1, YEAR_BITS));
+        }
+        return Some((end, YEAR_BITS));
+    }
+
+    // 1月1日
+    let (_, m) = month(i)?;
+    let end = i + m + 1;
+    if !at(i + m, '月') {
+        return None;
+    }
+    let (_, d) = day(end)?;
+    (at(end + d, '日') || at(end + d, '号')).then_some((end + d + 1, MONTH_BITS + DAY_BITS))
+}
+
+/// 从 `i` 起连续的字母或数字的结束位置，至少 3 个字
+fn sequence(chars: &[char], i: usize) -> Option<usize> {
+    let step = |a: char, b: char| (b as i32) - (a as i32);
+    let first = chars.get(i..i + 2)?;
+    let delta = step(first[0], first[1]);
+    if !first.iter().all(char::is_ascii_alphanumeric) || delta.abs() != 1 {
+        return None;
+    }
+    let mut end = i + 2;
+    while end < chars.len()
+        && chars[end].is_ascii_alphanumeric()
+        && step(chars[end - 1], chars[end]) == delta
+    {
+        end += 1;
+    }
+    (end - i >= 3).then_some(end)
+}
+
+/// 按攻击者最省力的猜法切分：各段的结束位置、类型和位数
+fn segment(chars: &[char]) -> Vec<(usize, Segment, f64)> {
+    let model = &*MODEL;
+    let lower = chars
+        .iter()
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect::<Vec<char>>();
+    // best[j]：前 j 个字的最少位数，以及最后一段的起始位置、类型和位数
+    let mut best = vec![(f64::INFINITY, 0, Segment::Char, 0.0); chars.len() + 1];
+    best[0].0 = 0.0;
+    for i in 0..chars.len() {
+        let base = best[i].0;
+        let mut relax = |end: usize, segment: Segment, bits: f64| {
+            if base + bits < best[end].0 {
+                best[end] = (base + bits, i, segment, bits);
+            }
+        };
+
+        relax(i + 1, Segment::Char, char_bits(chars[i]));
+        for end in i + 2..=(i + MAX_WORD_LEN).min(chars.len()) {
+            let word = chars[i..end].iter().collect::<String>();
+            if let Some(&bits) = model.words.get(&word) {
+                relax(end, Segment::Word, bits);
+            }
+        }
+        for end in i + 2..=(i + model.max_phrase_len).min(chars.len()) {
+            if model
+                .phrases
+                .contains(&lower[i..end].iter().collect::<String>())
+            {
+                relax(end, Segment::Phrase, model.phrase_bits);
+            }
+        }
+        if let Some((end, bits)) = date(chars, i) {
+            relax(end, Segment::Date, bits);
+        }
+        if let Some(end) = sequence(chars, i) {
+            relax(end, Segment::Sequence, 6.0 + ((end - i) as f64).log2());
+        }
+        for len in 1..=i.min(chars.len() - i).min(MAX_REPEAT_LEN) {
+            if chars[i - len..i] == chars[i..i + len] {
+                relax(i + len, Segment::Repeat, 1.0 + (len as f64).log2());
+            }
+        }
+    }
+
+    let mut segments = Vec::new();
+    let mut end = chars.len();
+    while end > 0 {
+        let (_, start, segment, bits) = best[end];
+        segments.push((end, segment, bits));
+        end = start;
+    }
+    segments.reverse();
+    segments
+}
+
+/// 估计心印的强度
+///
+/// 心印先按 v3 的规则规范化；破解成本假设攻击者用高端 GPU 按 `argon2` 参数逐句猜测，
+/// 每台设备的内存带宽为 1 TB/s、每小时 2 美元。v1 的字表绕过了 Argon2，不适用这个估计。
+pub fn heartprint_strength(text: &str, argon2: &Argon2Params) -> HeartprintStrength {
+    let chars = normalize_heartprint(text, false)
+        .chars()
+        .collect::<Vec<char>>();
+    let segments = segment(&chars);
+    let entropy_bits = segments.iter().map(|&(_, _, bits)| bits).sum::<f64>();
+
+    let mut warnings = Vec::new();
+    let len = chars.iter().filter(|ch| !ch.is_whitespace()).count();
+    if len < MIN_CHARS {
+        warnings.push(StrengthWarning::TooShort(len));
+    }
+    let mut start = 0;
+    // 最近一段不是重复的部分的起始位置
+    let mut repeated_from = 0;
+    for (i, &(end, segment, _)) in segments.iter().enumerate() {
+        if segment != Segment::Repeat {
+            repeated_from = start;
+        }
+        let text = chars[start..end].iter().collect::<String>();
+        match segment {
+            Segment::Phrase => warnings.push(StrengthWarning::KnownPhrase(text)),
+            Segment::Date => warnings.push(StrengthWarning::Date(text)),
+            Segment::Sequence => warnings.push(StrengthWarning::Pattern(text)),
+            // 重复的部分连同前面被重复的一段一起提示，如 `哈哈哈`、`abcabc`
+            Segment::Repeat if segments.get(i + 1).map(|s| s.1) != Some(Segment::Repeat) => {
+                let repeated = chars[repeated_from..end].iter().collect::<String>();
+                if repeated.chars().count() >= 3 {
+                    warnings.push(StrengthWarning::Pattern(repeated));
+                }
+            }
+            _ => {}
+        }
+        start = end;
+    }
+    warnings.dedup();
+
+    let bytes_per_guess =
+        f64::from(argon2.m_cost.max(1)) * 1024.0 * f64::from(argon2.t_cost.max(1)) * 3.0;
+    let guesses_per_second = DEVICE_BANDWIDTH / bytes_per_guess;
+    let guesses = (entropy_bits - 1.0).max(0.0).exp2();
+    let crack_seconds = guesses / guesses_per_second;
+    let crack_cost_usd = crack_seconds / 3600.0 * DEVICE_HOUR_USD;
+    let level = if crack_cost_usd < WEAK_COST_USD || len < MIN_CHARS {
+        StrengthLevel::Weak
+    } else if crack_cost_usd < FAIR_COST_USD {
+        StrengthLevel::Fair
+    } else {
+        StrengthLevel::Strong
+    };
+
+    HeartprintStrength {
+        entropy_bits,
+        guesses,
+        guesses_per_second,
+        crack_seconds,
+        crack_cost_usd,
+        level,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strength(text: &str) -> HeartprintStrength {
+        heartprint_strength(text, &Argon2Params::default())
+    }
+
+    #[test]
+    fn test_heartprint_strength_long_input() {
+        // 粘贴的长文本也要很快给出结果，重复的部分只按很少的位数计
+        let text = "心印不立文字".repeat(2000);
+        let long = strength(&text);
+        assert!(long.entropy_bits.is_finite());
+        assert!(long.entropy_bits < strength(&text[..text.len() / 2]).entropy_bits * 2.0);
+    }
+
+    #[test]
+    fn test_heartprint_strength() {
+        let weak = strength("1");
+        assert_eq!(weak.level, StrengthLevel::Weak);
+        assert_eq!(weak.warnings, vec![StrengthWarning::TooShort(1)]);
+
+        // 诗句和 README 示例中的心印按语料的条数计
+        for text in ["床前明月光，疑是地上霜。", "星辰大海，心自无疆。"] {
+            let known = strength(text);
+            assert_eq!(known.level, StrengthLevel::Weak, "`{}`", text);
+            assert!(known.entropy_bits < 40.0, "`{}`", text);
+            assert_eq!(
+                known
+                    .warnings
+                    .iter()
+                    .filter(|w| w.code() == "known_phrase")
+                    .count(),
+                2
+            );
+        }
+
+        let phrase = strength("我的生日是1990年1月1日，密码123456");
+        assert!(
+            phrase
+                .warnings
+                .contains(&StrengthWarning::Date("1990年1月1日".into()))
+        );
+        assert!(
+            phrase
+                .warnings
+                .contains(&StrengthWarning::Pattern("123456".into()))
+        );
+
+        let repeated = strength("哈哈哈哈哈哈哈哈哈哈哈哈");
+        assert_eq!(
+            repeated.warnings,
+            vec![StrengthWarning::Pattern("哈哈哈哈哈哈哈哈哈哈哈哈".into())]
+        );
+        assert_eq!(repeated.level, StrengthLevel::Weak);
+
+        let strong = strength("外婆家后院那棵歪脖子枣树，1998年夏天被雷劈成了两半");
+        assert_eq!(strong.level, StrengthLevel::Strong, "{:?}", strong);
+        assert_eq!(
+            strong.warnings,
+            vec![StrengthWarning::Date("1998年".into())]
+        );
+    }
+
+    #[test]
+    fn test_crack_cost() {
+        let text = "外婆家后院那棵歪脖子枣树";
+        let default = strength(text);
+        let stronger = heartprint_strength(
+            text,
+            &Argon2Params {
+                m_cost: 19456 * 4,
+                ..Argon2Params::default()
+            },
+        );
+        assert_eq!(default.entropy_bits, stronger.entropy_bits);
+        assert!((stronger.crack_cost_usd / default.crack_cost_usd - 4.0).abs() < 1e-9);
+        assert!(default.guesses_per_second > 1000.0);
+
+        // 全角标点、首尾空格不影响估计
+        assert_eq!(
+            strength("星辰大海，心自无疆。"),
+            strength(" 星辰大海,心自无疆. ")
+        );
+    }
+
+    #[test]
+    fn test_date() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        assert_eq!(date(&chars("19900101"), 0).map(|d| d.0), Some(8));
+        assert_eq!(date(&chars("1990-1-31"), 0).map(|d| d.0), Some(9));
+        assert_eq!(
+            date(&chars("二〇二四年十二月三十一日"), 0).map(|d| d.0),
+            Some(12)
+        );
+        assert_eq!(date(&chars("五月一号"), 0).map(|d| d.0), Some(4));
+        assert_eq!(date(&chars("2024年"), 0).map(|d| d.0), Some(5));
+        assert!(date(&chars("12345"), 0).is_none());
+        assert!(date(&chars("十三月一日"), 0).is_none());
+        assert_eq!(sequence(&chars("987654"), 0), Some(6));
+        assert!(sequence(&chars("13579"), 0).is_none());
+    }
+}
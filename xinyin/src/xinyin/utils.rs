@@ -1,20 +1,54 @@
-use anyhow::{Context, Result};
-use argon2::Argon2;
+use crate::Result;
+use crate::kdf::Argon2Params;
 use sha2::Digest;
+use zeroize::Zeroizing;
 
-pub(super) fn generate_cbc_key_iv(txt_in_heart: &str) -> Result<([u8; 32], [u8; 16])> {
+/// AES 密钥，释放时清零
+pub(super) type AesKey = Zeroizing<[u8; 32]>;
+
+pub(super) fn generate_cbc_key_iv(
+    txt_in_heart: &str,
+    argon2: &Argon2Params,
+) -> Result<(AesKey, [u8; 16])> {
     let argon2_salt = sha2::Sha256::digest(format!("{}-as-argon2-salt", txt_in_heart).as_bytes());
     let aes_iv = sha2::Sha256::digest(format!("{}-as-aes-iv", txt_in_heart).as_bytes());
 
-    let mut aes_key = [0u8; 32];
-    Argon2::default()
-        .hash_password_into(txt_in_heart.as_bytes(), &argon2_salt, &mut aes_key)
-        .context("failed to generate cbc key")?;
+    let mut aes_key = Zeroizing::new([0u8; 32]);
+    argon2.hash_password_into(txt_in_heart.as_bytes(), &argon2_salt, aes_key.as_mut())?;
+
+    let mut cbc_iv = [0u8; 16];
+    cbc_iv.copy_from_slice(&aes_iv[0..16]);
+    Ok((aes_key, cbc_iv))
+}
+
+/// v2：一次 Argon2 派生出 AES 密钥、CBC IV 和心印字表的种子
+///
+/// 字表和 IV 都依赖 Argon2 的输出，猜测心印时无法绕过 Argon2。
+///
+/// `label` 和 `salt` 非空时参与 Argon2 盐值，同一个心印在不同标签或盐下得到互不相关的密钥、IV 和字表。
+pub(super) fn generate_key_iv_seed(
+    txt_in_heart: &str,
+    label: &str,
+    salt: &[u8],
+    argon2: &Argon2Params,
+) -> Result<(AesKey, [u8; 16], [u8; 32])> {
+    let mut salt_txt = format!("{}-as-xinyin-v2-salt", txt_in_heart);
+    if !label.is_empty() {
+        salt_txt.push_str(&format!("-for-{}:{}", label.len(), label));
+    }
+    if !salt.is_empty() {
+        salt_txt.push_str("-with-");
+        salt_txt.extend(salt.iter().map(|b| format!("{:02x}", b)));
+    }
+    let argon2_salt = sha2::Sha256::digest(salt_txt.as_bytes());
+
+    let mut output = Zeroizing::new([0u8; 80]);
+    argon2.hash_password_into(txt_in_heart.as_bytes(), &argon2_salt, output.as_mut())?;
 
-    Ok((
-        aes_key,
-        aes_iv[0..16]
-            .try_into()
-            .context("cbc iv must be 16 bytes")?,
-    ))
+    let mut aes_key = Zeroizing::new([0u8; 32]);
+    let (mut aes_iv, mut seed) = ([0u8; 16], [0u8; 32]);
+    aes_key.copy_from_slice(&output[..32]);
+    aes_iv.copy_from_slice(&output[32..48]);
+    seed.copy_from_slice(&output[48..]);
+    Ok((aes_key, aes_iv, seed))
 }